@@ -0,0 +1,55 @@
+//! Bumps the released packages in `Cargo.lock` directly so no network access is needed.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::BooperError;
+use crate::{read_file, write_file};
+
+pub const LOCK_FILE: &str = "Cargo.lock";
+
+/// Rewrites the `version` of each `[[package]]` named in `packages` from one of `from_versions` to `to_version` in the lock file at `root`
+///
+/// Returns the path of the lock file if it was changed, a missing lock file is left alone
+pub fn update(
+    root: &Path,
+    packages: &[String],
+    from_versions: &[String],
+    to_version: &str,
+) -> Result<Option<PathBuf>, BooperError> {
+    let path = root.join(LOCK_FILE);
+    if packages.is_empty() || !path.exists() {
+        return Ok(None);
+    }
+    let contents = read_file(&path)?;
+    let mut new_contents = String::with_capacity(contents.len());
+    let mut in_package = false;
+    let mut changed = false;
+    for line in contents.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed.starts_with('[') {
+            in_package = false;
+        } else if let Some(name) = trimmed
+            .strip_prefix("name = \"")
+            .and_then(|name| name.strip_suffix('"'))
+        {
+            in_package = packages.iter().any(|package| package == name);
+        } else if in_package
+            && let Some(version) = trimmed
+                .strip_prefix("version = \"")
+                .and_then(|version| version.strip_suffix('"'))
+            && from_versions
+                .iter()
+                .any(|from_version| from_version == version)
+        {
+            new_contents.push_str(&line.replacen(version, to_version, 1));
+            changed = true;
+            continue;
+        }
+        new_contents.push_str(line);
+    }
+    if !changed {
+        return Ok(None);
+    }
+    write_file(&path, &new_contents)?;
+    Ok(Some(path))
+}
@@ -0,0 +1,89 @@
+//! Generates `CHANGELOG.md` sections from the commits since the last tag.
+
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::error::BooperError;
+use crate::{read_file, write_file};
+
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+const HEADER: &str = "# Changelog\n";
+
+/// Builds the section for `version` with a bullet per commit subject
+///
+/// With `grouped` the subjects are sorted into Features, Fixes and Other by their Conventional Commits type, which is left out
+pub fn section(version: &str, subjects: &[String], grouped: bool) -> String {
+    let date = chrono::Local::now().date_naive();
+    let mut section = format!("## [{version}] - {date}\n\n");
+    if !grouped {
+        push_bullets(&mut section, subjects.iter().map(String::as_str));
+        return section;
+    }
+    let regex = Regex::new(r"^(?<type>\w+)(\([^)]*\))?!?:\s*").unwrap();
+    let mut groups: [(&str, Vec<&str>); 3] = [
+        ("Features", Vec::new()),
+        ("Fixes", Vec::new()),
+        ("Other", Vec::new()),
+    ];
+    for subject in subjects {
+        let (group, description) = match regex.captures(subject) {
+            Some(caps) if &caps["type"] == "feat" => (0, &subject[caps.get_match().end()..]),
+            Some(caps) if &caps["type"] == "fix" => (1, &subject[caps.get_match().end()..]),
+            _ => (2, subject.as_str()),
+        };
+        groups[group].1.push(description);
+    }
+    for (heading, descriptions) in groups {
+        if descriptions.is_empty() {
+            continue;
+        }
+        if !section.ends_with("\n\n") {
+            section.push('\n');
+        }
+        section.push_str("### ");
+        section.push_str(heading);
+        section.push_str("\n\n");
+        push_bullets(&mut section, descriptions.into_iter());
+    }
+    section
+}
+
+fn push_bullets<'a>(section: &mut String, items: impl Iterator<Item = &'a str>) {
+    for item in items {
+        section.push_str("- ");
+        section.push_str(item);
+        section.push('\n');
+    }
+}
+
+/// Inserts `section` above the newest entry of the changelog at `root`, creating it with a header if it does not exist
+pub fn prepend(root: &Path, section: &str) -> Result<(), BooperError> {
+    let path = root.join(CHANGELOG_FILE);
+    let contents = if path.exists() {
+        read_file(&path)?
+    } else {
+        HEADER.to_owned()
+    };
+    let insert_at = contents
+        .match_indices("## ")
+        .map(|(i, _)| i)
+        .find(|&i| i == 0 || contents[..i].ends_with('\n'))
+        .unwrap_or(contents.len());
+    let (before, after) = contents.split_at(insert_at);
+    let mut new_contents = before.to_owned();
+    if !new_contents.is_empty() && !new_contents.ends_with("\n\n") {
+        new_contents.push_str(if new_contents.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        });
+    }
+    new_contents.push_str(section);
+    if !after.is_empty() {
+        new_contents.push('\n');
+        new_contents.push_str(after);
+    }
+    write_file(&path, &new_contents)
+}
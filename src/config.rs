@@ -0,0 +1,197 @@
+//! Per-project settings loaded from `.booper.toml` at the root of the repository.
+
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use regex::Regex;
+use semver::Version;
+use serde::{Deserialize, Deserializer};
+
+use crate::VersionIncrement;
+use crate::error::BooperError;
+use crate::matcher::Matcher;
+
+/// Name of the config file booper looks for
+pub const CONFIG_FILE: &str = ".booper.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Extra files to search for the current version in addition to the defaults
+    pub files: Vec<PathBuf>,
+
+    /// Increment used when none is given on the command line
+    #[serde(alias = "default_increment", deserialize_with = "deserialize_from_str")]
+    pub increment: Option<VersionIncrement>,
+
+    /// Prerelease labels in the order `promote` moves through them, defaults to `["alpha", "beta", "rc"]`
+    #[serde(deserialize_with = "deserialize_labels")]
+    pub promote_ladder: Vec<String>,
+
+    /// Commit message template, `{version}`, `{from_version}` and `{tag}` are replaced
+    pub commit_message: Option<String>,
+
+    /// Annotated tag message template with the same placeholders as `commit_message`. Defaults to the commit message
+    pub tag_message: Option<String>,
+
+    /// Branch releases must be made from
+    pub branch: Option<String>,
+
+    /// Shell commands run after the version files are written but before committing
+    pub pre_hooks: Vec<String>,
+
+    /// Shell commands run after committing, tagging and pushing
+    pub post_hooks: Vec<String>,
+
+    /// Prefix for release tags, inferred from the last tag when unset
+    pub tag_prefix: Option<String>,
+
+    /// What to do with version files that are ignored by git
+    pub ignored_files: IgnoredFiles,
+
+    /// Go file declaring `const Version = "1.2.3"` to search for the current version, tags always get the `v` prefix Go modules need
+    pub go_version_file: Option<PathBuf>,
+
+    /// Rust file declaring `const VERSION: &str = "1.2.3";` to search for the current version alongside `Cargo.toml`
+    pub rust_version_file: Option<PathBuf>,
+
+    /// Forms of the version to bump in a `Dockerfile`, which is only searched for the current version when this is set
+    pub dockerfile: Vec<DockerfileVersion>,
+
+    /// Files whose version is kept a fixed distance from the main version
+    #[serde(rename = "offset")]
+    pub offsets: Vec<Offset>,
+
+    /// Files where the version is found by its key or a pattern, which are searched for the current version
+    #[serde(rename = "match")]
+    pub matchers: Vec<Matcher>,
+
+    /// Extra patterns for mentions of the version that can drift, like install snippets in a readme
+    #[serde(rename = "replace")]
+    pub replacements: Vec<Replacement>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Replacement {
+    pub file: PathBuf,
+
+    /// Replaces the `version` capture group if there is one, otherwise the version number within each match
+    #[serde(deserialize_with = "deserialize_regex")]
+    pub pattern: Regex,
+}
+
+/// A file versioned in lockstep with the main version, like a plugin at `1.5.0` released alongside its app at `2.5.0`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Offset {
+    pub file: PathBuf,
+
+    /// Added to the main version to get the file's version, so the plugin has `major = -1`
+    #[serde(default)]
+    pub major: i64,
+    #[serde(default)]
+    pub minor: i64,
+    #[serde(default)]
+    pub patch: i64,
+}
+
+impl Offset {
+    /// The file's version for the main `version`, `None` if a number would go below zero
+    pub fn apply(&self, version: &Version) -> Option<Version> {
+        self.shift(version, 1)
+    }
+
+    /// The main version for the file's `version`, `None` if a number would go below zero
+    pub fn remove(&self, version: &Version) -> Option<Version> {
+        self.shift(version, -1)
+    }
+
+    fn shift(&self, version: &Version, sign: i64) -> Option<Version> {
+        Some(Version {
+            major: version.major.checked_add_signed(self.major * sign)?,
+            minor: version.minor.checked_add_signed(self.minor * sign)?,
+            patch: version.patch.checked_add_signed(self.patch * sign)?,
+            ..version.clone()
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnoredFiles {
+    /// Leave them out with a warning
+    #[default]
+    Skip,
+    /// Refuse to release
+    Error,
+}
+
+/// Where a `Dockerfile` can mention the version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DockerfileVersion {
+    /// `ARG VERSION=1.2.3`
+    Arg,
+    /// `LABEL version="1.2.3"`
+    Label,
+}
+
+impl Config {
+    /// Reads the config file at the repository `root`, using the defaults when there isn't one
+    pub fn load(root: &Path) -> Result<Self, BooperError> {
+        let contents = match std::fs::read_to_string(root.join(CONFIG_FILE)) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(BooperError::ConfigRead(err)),
+        };
+        toml::from_str(&contents).map_err(BooperError::ConfigParse)
+    }
+}
+
+pub fn deserialize_regex<'de, D>(deserializer: D) -> Result<Regex, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Regex::new(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Prerelease labels, each checked the same way as `pre=<label>` so bumping with them can't fail
+fn deserialize_labels<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let labels = Vec::<String>::deserialize(deserializer)?;
+    for label in &labels {
+        semver::Prerelease::new(&format!("{label}.1")).map_err(|err| {
+            serde::de::Error::custom(format!("invalid prerelease label {label:?}: {err}"))
+        })?;
+    }
+    Ok(labels)
+}
+
+fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn promote_ladder_labels_are_checked() {
+        let config: Config = toml::from_str(r#"promote_ladder = ["alpha", "rc"]"#).unwrap();
+        assert_eq!(config.promote_ladder, ["alpha", "rc"]);
+        assert!(toml::from_str::<Config>(r#"promote_ladder = ["alpha_1"]"#).is_err());
+        assert!(toml::from_str::<Config>(r#"promote_ladder = [""]"#).is_err());
+    }
+}
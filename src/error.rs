@@ -0,0 +1,278 @@
+//! Errors that stop a release.
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::process::{ExitCode, ExitStatus};
+
+/// Why a release stopped
+#[derive(Debug)]
+pub enum BooperError {
+    /// The user declined the confirm prompt
+    Cancelled,
+    /// The confirm prompt could not be shown
+    Prompt(dialoguer::Error),
+    /// `.booper.toml` exists but could not be read
+    ConfigRead(std::io::Error),
+    /// `.booper.toml` is not valid
+    ConfigParse(toml::de::Error),
+    /// Reading or writing a version file failed
+    File {
+        /// The file being read or written
+        path: PathBuf,
+        /// Why it failed
+        source: std::io::Error,
+    },
+    /// Searching for files to update failed
+    Walk(ignore::Error),
+    /// None of the version files contain a version
+    NoVersionsFound,
+    /// `--package` named a package that is not in the workspace
+    PackageNotFound(String),
+    /// The version files disagree, holds the version found in each
+    InconsistentVersions(Vec<String>),
+    /// A version is not valid semver
+    InvalidVersion {
+        /// The text that failed to parse
+        version: String,
+        /// Why it is not valid
+        source: semver::Error,
+    },
+    /// A version file has something that is not valid semver
+    InvalidFileVersion {
+        /// The version file
+        path: PathBuf,
+        /// The text that failed to parse
+        version: String,
+        /// Why it is not valid
+        source: semver::Error,
+    },
+    /// An `[[offset]]` would take a version number below zero
+    OffsetOutOfRange {
+        /// The offset file
+        path: PathBuf,
+        /// The version the offset was applied to
+        version: String,
+    },
+    /// A file was rewritten but does not contain the new version
+    NotUpdated(PathBuf),
+    /// The last tag is for a different version than the one in the files
+    TagMismatch {
+        /// The last tag
+        tag: String,
+        /// The version in the files
+        version: String,
+    },
+    /// The tag for the new version already exists
+    TagExists(String),
+    /// The working tree has changes that would end up in the release commit
+    UncommittedChanges,
+    /// A version file is gitignored and `ignored_files = "error"` is set
+    IgnoredVersionFile(PathBuf),
+    /// The remote can't be reached so pushing would fail after committing
+    RemoteUnreachable {
+        /// The remote name
+        remote: String,
+        /// What `git ls-remote` printed
+        stderr: String,
+    },
+    /// Releases are restricted to another branch
+    WrongBranch {
+        /// The branch releases must be made from
+        expected: String,
+        /// The current branch
+        actual: String,
+    },
+    /// A flag was given that only makes sense alongside -c / --commit
+    RequiresCommit(&'static str),
+    /// A flag was given that needs an exact version rather than an increment
+    RequiresExactVersion(&'static str),
+    /// A flag was given that needs the tag to be created and pushed
+    RequiresPushedTag(&'static str),
+    /// `booper undo` found a HEAD commit that booper did not make
+    NotReleaseCommit {
+        /// The HEAD commit message
+        message: String,
+        /// The version a release commit would be for
+        version: String,
+    },
+    /// `booper undo` found tags on HEAD that don't name the version, which would be left on the reset commit
+    UnknownTags(Vec<String>),
+    /// The remote url is not a GitHub repository
+    NotGitHub(String),
+    /// A command could not be started
+    Spawn {
+        /// The program being run
+        program: String,
+        /// Why it could not be started
+        source: std::io::Error,
+    },
+    /// A command exited unsuccessfully
+    CommandFailed {
+        /// What the command was for
+        what: &'static str,
+        /// What it printed to stderr, when that explains the failure
+        stderr: Option<String>,
+    },
+    /// A pre or post hook exited unsuccessfully
+    HookFailed {
+        /// The shell command
+        hook: String,
+        /// How it exited
+        status: ExitStatus,
+    },
+    /// A dry run with `--exit-nonzero-on-change` would release this version
+    WouldRelease(String),
+    /// The `--json` summary could not be written
+    Json(serde_json::Error),
+}
+
+impl BooperError {
+    /// Declining the prompt exits with 2 and a dry run that would release with 3 so scripts can tell why the release stopped
+    ///
+    /// Every other error exits with 1, including failed hooks whose own exit code is in the message so it can't be mistaken for 2 or 3
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Cancelled => ExitCode::from(2),
+            Self::WouldRelease(_) => ExitCode::from(3),
+            _ => ExitCode::FAILURE,
+        }
+    }
+}
+
+impl Display for BooperError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::Prompt(err) => write!(f, "prompt failed: {err}"),
+            Self::ConfigRead(err) => {
+                write!(f, "failed to read {}: {err}", crate::config::CONFIG_FILE)
+            }
+            Self::ConfigParse(err) => {
+                write!(f, "failed to parse {}: {err}", crate::config::CONFIG_FILE)
+            }
+            Self::File { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::Walk(err) => write!(f, "failed to search files: {err}"),
+            Self::NoVersionsFound => write!(f, "no versions found"),
+            Self::PackageNotFound(name) => write!(f, "no Cargo.toml found for package {name}"),
+            Self::InconsistentVersions(versions) => {
+                write!(
+                    f,
+                    "no consistent version found: {versions:?}, use --reconcile to bump from the highest"
+                )
+            }
+            Self::InvalidVersion { version, source } => {
+                write!(f, "invalid version {version:?}: {source}")
+            }
+            Self::InvalidFileVersion {
+                path,
+                version,
+                source,
+            } => {
+                write!(
+                    f,
+                    "{} has invalid version {version:?}: {source}",
+                    path.display()
+                )?;
+                let core = version.split(['-', '+']).next().unwrap_or_default();
+                let is_short = core.matches('.').count() < 2
+                    && core.starts_with(|c: char| c.is_ascii_digit())
+                    && core.chars().all(|c| c.is_ascii_digit() || c == '.');
+                if is_short {
+                    write!(f, ", use --pad-versions to fill in the missing numbers")?;
+                }
+                Ok(())
+            }
+            Self::TagMismatch { tag, version } => write!(
+                f,
+                "last git tag {tag} does not match the detected version {version}, use --no-tag-check to skip this check"
+            ),
+            Self::OffsetOutOfRange { path, version } => write!(
+                f,
+                "the offset for {} takes {version} below zero",
+                path.display()
+            ),
+            Self::NotUpdated(path) => write!(
+                f,
+                "{} does not contain the new version after updating it",
+                path.display()
+            ),
+            Self::TagExists(tag) => write!(f, "tag {tag} already exists"),
+            Self::UncommittedChanges => write!(f, "uncommitted changes"),
+            Self::IgnoredVersionFile(path) => write!(
+                f,
+                "{} is ignored by git so its version change would not be committed",
+                path.display()
+            ),
+            Self::RemoteUnreachable { remote, stderr } => {
+                write!(f, "can't push as remote {remote} is unreachable: {stderr}")
+            }
+            Self::WrongBranch { expected, actual } => write!(
+                f,
+                "releases must be made from {expected} but the current branch is {actual}, use --force-branch to override"
+            ),
+            Self::RequiresCommit(what) => {
+                write!(f, "can't {what} when -c / --commit is not enabled")
+            }
+            Self::RequiresExactVersion(flag) => {
+                write!(
+                    f,
+                    "--{flag} needs an exact version e.g. `1.0.3` as the increment"
+                )
+            }
+            Self::RequiresPushedTag(what) => write!(
+                f,
+                "can't {what} unless -c / --commit, -t / --tag and -p / --push are enabled"
+            ),
+            Self::NotReleaseCommit { message, version } => write!(
+                f,
+                "HEAD commit {message:?} does not look like the release commit for {version}"
+            ),
+            Self::UnknownTags(tags) => write!(f, "HEAD has tags {tags:?} not from the release"),
+            Self::NotGitHub(url) => write!(f, "remote {url} is not a GitHub repository"),
+            Self::Spawn { program, source } => write!(f, "failed to run {program}: {source}"),
+            Self::CommandFailed { what, stderr: None } => write!(f, "{what} failed"),
+            Self::CommandFailed {
+                what,
+                stderr: Some(stderr),
+            } => write!(f, "{what} failed: {stderr}"),
+            Self::WouldRelease(version) => write!(f, "a release of {version} is due"),
+            Self::Json(err) => write!(f, "failed to write json output: {err}"),
+            Self::HookFailed { hook, status } => write!(f, "hook `{hook}` failed with {status}"),
+        }
+    }
+}
+
+impl std::error::Error for BooperError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Prompt(err) => Some(err),
+            Self::ConfigRead(err)
+            | Self::File { source: err, .. }
+            | Self::Spawn { source: err, .. } => Some(err),
+            Self::ConfigParse(err) => Some(err),
+            Self::Walk(err) => Some(err),
+            Self::Json(err) => Some(err),
+            Self::InvalidVersion { source, .. } | Self::InvalidFileVersion { source, .. } => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<dialoguer::Error> for BooperError {
+    fn from(err: dialoguer::Error) -> Self {
+        Self::Prompt(err)
+    }
+}
+
+impl From<ignore::Error> for BooperError {
+    fn from(err: ignore::Error) -> Self {
+        Self::Walk(err)
+    }
+}
+
+/// Returns `err` unless `check` holds
+pub fn ensure(check: bool, err: BooperError) -> Result<(), BooperError> {
+    if check { Ok(()) } else { Err(err) }
+}
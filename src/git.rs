@@ -0,0 +1,352 @@
+//! The git commands booper runs.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::error::{BooperError, ensure};
+use crate::{Verbosity, output, run, run_reporting_stderr, status};
+
+#[derive(Debug, Clone, Copy)]
+pub struct CommitOptions<'a> {
+    pub sign: bool,
+    /// Key to sign with instead of git's default
+    pub signing_key: Option<&'a str>,
+    /// Adds a `Signed-off-by` trailer for the Developer Certificate of Origin
+    pub signoff: bool,
+    pub no_verify: bool,
+    /// Folds the changes into the previous commit instead of making a new one
+    pub amend: bool,
+    /// Needed when the version only lives in tags so there is nothing to commit
+    pub allow_empty: bool,
+    /// Commits every tracked change rather than only what is staged
+    pub all: bool,
+}
+
+/// Where an annotated tag gets its message from
+#[derive(Debug, Clone, Copy)]
+pub enum TagMessage<'a> {
+    Inline(&'a str),
+    File(&'a Path),
+}
+
+pub struct Git {
+    verbosity: Verbosity,
+    /// The git executable
+    program: PathBuf,
+}
+
+impl Git {
+    pub const fn new(verbosity: Verbosity, program: PathBuf) -> Self {
+        Self { verbosity, program }
+    }
+
+    fn command(&self) -> Command {
+        Command::new(&self.program)
+    }
+
+    /// Runs git with `args` returning its trimmed stdout, failing with `what` and the stderr if it exits unsuccessfully
+    fn capture(&self, args: &[&str], what: &'static str) -> Result<String, BooperError> {
+        let mut cmd = self.command();
+        cmd.args(args);
+        let output = output(&mut cmd, self.verbosity)?;
+        if !output.status.success() {
+            return Err(BooperError::CommandFailed {
+                what,
+                stderr: Some(String::from_utf8_lossy(&output.stderr).trim().to_owned()),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+
+    pub fn assert_clean(&self) -> Result<(), BooperError> {
+        let mut cmd = self.command();
+        cmd.args(["diff", "--quiet"]);
+        let status = status(&mut cmd, self.verbosity)?;
+        ensure(status.success(), BooperError::UncommittedChanges)
+    }
+
+    pub fn has_uncommitted_changes(&self) -> Result<bool, BooperError> {
+        let mut cmd = self.command();
+        cmd.args(["diff", "HEAD", "--quiet"]);
+        Ok(!status(&mut cmd, self.verbosity)?.success())
+    }
+
+    /// Which of `paths` are ignored by git
+    pub fn ignored(&self, paths: &[PathBuf]) -> Result<Vec<PathBuf>, BooperError> {
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut cmd = self.command();
+        cmd.args(["check-ignore", "--"]).args(paths);
+        let output = output(&mut cmd, self.verbosity)?;
+        // Exits with 1 when nothing is ignored
+        if !output.status.success() && output.status.code() != Some(1) {
+            return Err(BooperError::CommandFailed {
+                what: "git check-ignore",
+                stderr: Some(String::from_utf8_lossy(&output.stderr).trim().to_owned()),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(PathBuf::from)
+            .collect())
+    }
+
+    pub fn stage(&self, path: &Path) -> Result<(), BooperError> {
+        run(
+            self.command().arg("add").arg(path),
+            "git add",
+            self.verbosity,
+        )
+    }
+
+    /// Commits the staged changes and with `all` every tracked change, `message` and `body` are unused when amending as the previous message is kept
+    pub fn commit(
+        &self,
+        message: &str,
+        body: Option<&str>,
+        options: CommitOptions<'_>,
+    ) -> Result<(), BooperError> {
+        let mut cmd = self.command();
+        cmd.arg("commit");
+        if options.all {
+            cmd.arg("-a");
+        }
+        if options.amend {
+            cmd.args(["--amend", "--no-edit"]);
+        } else {
+            cmd.args(["-m", message]);
+            // Each `-m` is its own paragraph
+            if let Some(body) = body {
+                cmd.args(["-m", body]);
+            }
+        }
+        if options.sign {
+            match options.signing_key {
+                Some(key) => cmd.arg(format!("--gpg-sign={key}")),
+                None => cmd.arg("-S"),
+            };
+        }
+        if options.signoff {
+            cmd.arg("--signoff");
+        }
+        if options.no_verify {
+            cmd.arg("--no-verify");
+        }
+        if options.allow_empty {
+            cmd.arg("--allow-empty");
+        }
+        run_reporting_stderr(&mut cmd, "commit", self.verbosity)
+    }
+
+    /// Fails if `remote` can't be contacted, an empty remote still counts as reachable
+    pub fn assert_remote_reachable(&self, remote: &str) -> Result<(), BooperError> {
+        let mut cmd = self.command();
+        cmd.args(["ls-remote", "--exit-code", remote]);
+        let output = output(&mut cmd, self.verbosity)?;
+        // Exits with 2 when the remote has no refs
+        ensure(
+            output.status.success() || output.status.code() == Some(2),
+            BooperError::RemoteUnreachable {
+                remote: remote.to_owned(),
+                // The first line has the cause, the rest is generic advice
+                stderr: String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_owned(),
+            },
+        )
+    }
+
+    /// Pushes HEAD, trying again up to `retries` times if it fails
+    pub fn push(&self, remote: &str, retries: u32) -> Result<(), BooperError> {
+        self.retrying(retries, || {
+            run(
+                self.command().args(["push", remote, "HEAD"]),
+                "push",
+                self.verbosity,
+            )
+        })
+    }
+
+    pub fn tag(
+        &self,
+        tag: &str,
+        message: Option<TagMessage<'_>>,
+        sign: bool,
+        signing_key: Option<&str>,
+    ) -> Result<(), BooperError> {
+        let mut cmd = self.command();
+        cmd.arg("tag");
+        if sign {
+            match signing_key {
+                Some(key) => cmd.args(["-u", key]),
+                None => cmd.arg("-s"),
+            };
+        } else if message.is_some() {
+            cmd.arg("-a");
+        }
+        match message {
+            Some(TagMessage::Inline(message)) => {
+                cmd.args(["-m", message]);
+            }
+            Some(TagMessage::File(path)) => {
+                cmd.arg("-F").arg(path);
+            }
+            None => {}
+        }
+        run_reporting_stderr(cmd.arg(tag), "tag", self.verbosity)
+    }
+
+    pub fn tag_exists(&self, tag: &str) -> Result<bool, BooperError> {
+        let mut cmd = self.command();
+        cmd.args(["rev-parse", "--quiet", "--verify"])
+            .arg(format!("refs/tags/{tag}"));
+        Ok(output(&mut cmd, self.verbosity)?.status.success())
+    }
+
+    pub fn delete_tag(&self, tag: &str) -> Result<(), BooperError> {
+        run(
+            self.command().args(["tag", "-d", tag]),
+            "delete tag",
+            self.verbosity,
+        )
+    }
+
+    pub fn delete_remote_tag(&self, remote: &str, tag: &str) -> Result<(), BooperError> {
+        run_reporting_stderr(
+            self.command()
+                .args(["push", "--delete", remote])
+                .arg(format!("refs/tags/{tag}")),
+            "delete remote tag",
+            self.verbosity,
+        )
+    }
+
+    pub fn reset_hard(&self, revision: &str) -> Result<(), BooperError> {
+        run(
+            self.command().args(["reset", "--hard", revision]),
+            "reset",
+            self.verbosity,
+        )
+    }
+
+    /// Full message of the HEAD commit with surrounding whitespace trimmed
+    pub fn head_message(&self) -> Result<String, BooperError> {
+        self.capture(&["log", "-1", "--pretty=%B"], "git log")
+    }
+
+    pub fn tags_at_head(&self) -> Result<Vec<String>, BooperError> {
+        Ok(self
+            .capture(&["tag", "--points-at", "HEAD"], "git tag")?
+            .lines()
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+
+    /// Pushes HEAD along with the annotated tags pointing into it, trying again up to `retries` times if it fails
+    pub fn push_follow_tags(&self, remote: &str, retries: u32) -> Result<(), BooperError> {
+        self.retrying(retries, || {
+            run(
+                self.command()
+                    .args(["push", "--follow-tags", remote, "HEAD"]),
+                "push",
+                self.verbosity,
+            )
+        })
+    }
+
+    /// Pushes `tag`, trying again up to `retries` times if it fails. With `force` a different tag of the same name on the remote is replaced
+    pub fn push_tag(
+        &self,
+        remote: &str,
+        tag: &str,
+        force: bool,
+        retries: u32,
+    ) -> Result<(), BooperError> {
+        self.retrying(retries, || {
+            let mut cmd = self.command();
+            cmd.args(["push", remote, tag]);
+            if force {
+                cmd.arg("--force");
+            }
+            run(&mut cmd, "push tag", self.verbosity)
+        })
+    }
+
+    /// Runs `attempt` until it succeeds or has failed `retries` more times, waiting twice as long each time starting from a second
+    fn retrying(
+        &self,
+        retries: u32,
+        attempt: impl Fn() -> Result<(), BooperError>,
+    ) -> Result<(), BooperError> {
+        let mut delay = Duration::from_secs(1);
+        for _ in 0..retries {
+            match attempt() {
+                Err(BooperError::CommandFailed { what, .. }) => {
+                    if self.verbosity != Verbosity::Quiet {
+                        eprintln!("{what} failed, retrying in {}s", delay.as_secs());
+                    }
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+        attempt()
+    }
+
+    /// The root of the working tree, `None` outside a repository
+    pub fn toplevel(&self) -> Result<Option<PathBuf>, BooperError> {
+        let mut cmd = self.command();
+        cmd.args(["rev-parse", "--show-toplevel"]);
+        let output = output(&mut cmd, self.verbosity)?;
+        Ok(output
+            .status
+            .success()
+            .then(|| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim())))
+    }
+
+    pub fn current_branch(&self) -> Result<String, BooperError> {
+        self.capture(&["rev-parse", "--abbrev-ref", "HEAD"], "git rev-parse")
+    }
+
+    pub fn remote_url(&self, remote: &str) -> Result<String, BooperError> {
+        self.capture(&["remote", "get-url", remote], "git remote get-url")
+    }
+
+    /// Most recent tag reachable from HEAD, only considering tags starting with `prefix` if given
+    ///
+    /// Without a prefix the `<package>-v<version>` tags of `--package` releases are left out as they belong to another version
+    pub fn last_tag(&self, prefix: Option<&str>) -> Result<Option<String>, BooperError> {
+        let mut cmd = self.command();
+        cmd.args(["describe", "--tags", "--abbrev=0"]);
+        if let Some(prefix) = prefix {
+            cmd.arg(format!("--match={prefix}*"));
+        } else {
+            cmd.arg("--exclude=*-v[0-9]*");
+        }
+        let output = output(&mut cmd, self.verbosity)?;
+        if output.status.success() {
+            Ok(Some(
+                String::from_utf8_lossy(&output.stdout).trim().to_owned(),
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Subjects of the commits after `last_tag`, or of every commit when there are no tags yet
+    pub fn commit_subjects(&self, last_tag: Option<&str>) -> Result<Vec<String>, BooperError> {
+        let range = last_tag.map(|last_tag| format!("{last_tag}..HEAD"));
+        let mut args = vec!["log", "--pretty=%s"];
+        args.extend(range.as_deref());
+        Ok(self
+            .capture(&args, "git log")?
+            .lines()
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+}
@@ -0,0 +1,35 @@
+//! Creates GitHub releases using the `gh` cli, which authenticates with `GH_TOKEN` / `GITHUB_TOKEN` or `gh auth login`.
+
+use std::process::Command;
+
+use crate::error::BooperError;
+use crate::{Verbosity, run_reporting_stderr};
+
+/// Extracts `owner/repo` from an ssh or https GitHub remote url
+fn repo_from_url(url: &str) -> Option<&str> {
+    let path = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.split_once("github.com/").map(|(_, path)| path))?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    (path.split('/').count() == 2).then_some(path)
+}
+
+/// Creates a release for the already pushed `tag`, using `notes` as the body or generating notes from the commits
+pub fn create_release(
+    remote_url: &str,
+    tag: &str,
+    notes: Option<&str>,
+    verbosity: Verbosity,
+) -> Result<(), BooperError> {
+    let repo =
+        repo_from_url(remote_url).ok_or_else(|| BooperError::NotGitHub(remote_url.to_owned()))?;
+    let mut cmd = Command::new("gh");
+    cmd.args(["release", "create", tag, "--repo", repo, "--title", tag]);
+    if let Some(notes) = notes {
+        cmd.args(["--notes", notes]);
+    } else {
+        cmd.arg("--generate-notes");
+    }
+    run_reporting_stderr(&mut cmd, "gh release create", verbosity)
+}
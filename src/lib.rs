@@ -0,0 +1,3102 @@
+#![warn(missing_docs)]
+
+//! Booper is a cli tool to increment version numbers for projects commit them, tag a release and push it using git.
+//!
+//! The main use case is that you have a project that you want to release a new version this involves changing the Cargo.toml version number.
+//! Updating the Cargo.lock file, checking the project still builds and incrementing any other places the version is mentioned.
+//! Committing this as a new change, tagging it and pushing it.
+//!
+//! Booper simplifies this into one simple command `booper -ctp` or `booper -ctp minor`
+//!
+//! Booper will search for versions in common places and ask if you want to increment them.
+//!
+//! The same release can be made from another tool by building a [`Booper`] and calling [`Booper::run`].
+//!
+//! Currently booper only checks `Cargo.toml`, `.env`, `package.json`, `composer.json`, `deno.json`, `deno.jsonc`, `pyproject.toml`, `setup.py`, `setup.cfg`, `pubspec.yaml`, `Chart.yaml`, `CITATION.cff`, `Makefile`, `pom.xml`, `*.csproj`, `*.gemspec`, an opt-in `Dockerfile`, Go or Rust file and bare `VERSION` / `version.txt` files but this is likely to expand in the future.
+
+mod cargo_lock;
+mod changelog;
+mod config;
+mod error;
+mod git;
+mod github;
+mod matcher;
+mod npm_lock;
+
+pub use crate::error::BooperError;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Write as _};
+use std::io::IsTerminal as _;
+use std::ops::Range;
+use std::path::{Component, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::{path::Path, str::FromStr};
+
+use chrono::Datelike as _;
+use clap::{Parser, Subcommand};
+use regex::{Captures, Regex};
+use semver::Version;
+use serde::Serialize;
+
+use crate::config::{Config, DockerfileVersion, IgnoredFiles, Offset, Replacement};
+use crate::error::ensure;
+use crate::git::{CommitOptions, Git, TagMessage};
+use crate::matcher::{Matcher, string_values};
+
+/// Files searched for the current version, more can be added with `--file` or `files` in `.booper.toml`
+pub const DEFAULT_FILES: [&str; 16] = [
+    "Cargo.toml",
+    ".env",
+    "package.json",
+    "composer.json",
+    "deno.json",
+    "deno.jsonc",
+    "pyproject.toml",
+    "setup.py",
+    "setup.cfg",
+    "pubspec.yaml",
+    "Chart.yaml",
+    "CITATION.cff",
+    "Makefile",
+    "pom.xml",
+    "VERSION",
+    "version.txt",
+];
+
+const DEFAULT_COMMIT_MESSAGE: &str = "Version {version}";
+
+/// Prerelease labels `promote` moves through when `promote_ladder` is not set
+const DEFAULT_PROMOTE_LADDER: [&str; 3] = ["alpha", "beta", "rc"];
+
+/// Prints informational output to stderr unless `--quiet` is given
+macro_rules! info {
+    ($verbosity:expr, $($arg:tt)*) => {
+        if $verbosity != $crate::Verbosity::Quiet {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// How to get from the current version to the new one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionIncrement {
+    /// `Patch`, or `StripPrerelease` for a prerelease, or with `--conventional` whatever the commits imply
+    Auto,
+    /// `1.2.3` to `1.2.4`, a prerelease is dropped unless `--keep-pre` is given
+    Patch,
+    /// `1.2.3` to `1.3.0`, a prerelease is dropped unless `--keep-pre` is given
+    Minor,
+    /// `1.2.3` to `2.0.0`, a prerelease is dropped unless `--keep-pre` is given
+    Major,
+    /// `1.2.3-rc.1` to `1.2.3`
+    StripPrerelease,
+    /// Bumps the prerelease counter, `None` keeps the current label
+    Prerelease(Option<String>),
+    /// Bumps with `Patch`, `Minor` or `Major` then starts a prerelease, so `minor-rc` takes `1.2.3` to `1.3.0-rc.1`. `None` uses `pre`
+    PreBump(Box<Self>, Option<String>),
+    /// Moves the prerelease to the next label of the ladder, `alpha`, `beta` then `rc` by default, or to the release after the last one
+    ///
+    /// A release starts the ladder for the next patch
+    Promote,
+    /// Calendar versioning `YEAR.MONTH.N` where `N` counts releases within the month
+    CalVer,
+    /// Bumps a counter in the build metadata leaving the rest of the version alone
+    Build,
+    /// Sets this version
+    Exact(Version),
+}
+
+impl FromStr for VersionIncrement {
+    type Err = semver::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_ref() {
+            "auto" => Self::Auto,
+            "patch" => Self::Patch,
+            "minor" => Self::Minor,
+            "major" => Self::Major,
+            "strip" => Self::StripPrerelease,
+            "calver" => Self::CalVer,
+            "build" => Self::Build,
+            "promote" => Self::Promote,
+            "pre" => Self::Prerelease(None),
+            label @ ("alpha" | "beta" | "rc") => Self::Prerelease(Some(label.to_owned())),
+            _ => match s.split_once('=') {
+                Some((key, label)) if key.eq_ignore_ascii_case("pre") => {
+                    semver::Prerelease::new(&format!("{label}.1"))?;
+                    Self::Prerelease(Some(label.to_owned()))
+                }
+                _ => match s.split_once('-') {
+                    Some((core, pre))
+                        if let Ok(core @ (Self::Patch | Self::Minor | Self::Major)) =
+                            core.parse() =>
+                    {
+                        match pre.parse()? {
+                            Self::Prerelease(label) => Self::PreBump(Box::new(core), label),
+                            _ => Self::Exact(Version::from_str(s)?),
+                        }
+                    }
+                    _ => Self::Exact(Version::from_str(s)?),
+                },
+            },
+        })
+    }
+}
+
+impl VersionIncrement {
+    /// The version after `current`, build metadata is dropped unless an exact version is given or it is the build being bumped
+    ///
+    /// `Promote` uses the default ladder, `promote_ladder` in `.booper.toml` only applies to releases made with [`Booper::run`]
+    pub fn increment(&self, current: &Version) -> Version {
+        let mut version = match self {
+            Self::Auto => {
+                if current.pre.is_empty() {
+                    Self::Patch.increment(current)
+                } else {
+                    Self::StripPrerelease.increment(current)
+                }
+            }
+            Self::Patch => Version::new(current.major, current.minor, current.patch + 1),
+            Self::Minor => Version::new(current.major, current.minor + 1, 0),
+            Self::Major => Version::new(current.major + 1, 0, 0),
+            Self::StripPrerelease => Version {
+                pre: semver::Prerelease::default(),
+                ..current.clone()
+            },
+            Self::Prerelease(label) => Version {
+                pre: bump_prerelease(&current.pre, label.as_deref()),
+                ..current.clone()
+            },
+            Self::PreBump(core, label) => Version {
+                pre: bump_prerelease(&semver::Prerelease::EMPTY, label.as_deref()),
+                ..core.increment(current)
+            },
+            Self::Promote => {
+                let ladder = DEFAULT_PROMOTE_LADDER.map(str::to_owned);
+                promote_increment(current, &ladder).increment(current)
+            }
+            Self::CalVer => {
+                let today = chrono::Local::now().date_naive();
+                let year = u64::try_from(today.year()).unwrap_or_default();
+                let month = u64::from(today.month());
+                let patch = if current.major == year && current.minor == month {
+                    current.patch + 1
+                } else {
+                    0
+                };
+                Version::new(year, month, patch)
+            }
+            Self::Build => Version {
+                build: bump_build(&current.build),
+                ..current.clone()
+            },
+            Self::Exact(version) => version.clone(),
+        };
+        if !matches!(self, Self::Exact(_) | Self::Build) {
+            version.build = semver::BuildMetadata::EMPTY;
+        }
+        version
+    }
+}
+
+/// The concrete increment for promoting `current` one rung up `ladder`, a label not on the ladder is promoted to the release
+fn promote_increment(current: &Version, ladder: &[String]) -> VersionIncrement {
+    if current.pre.is_empty() {
+        let Some(first) = ladder.first() else {
+            return VersionIncrement::Patch;
+        };
+        let mut version = VersionIncrement::Patch.increment(current);
+        version.pre = bump_prerelease(&version.pre, Some(first));
+        return VersionIncrement::Exact(version);
+    }
+    let label = current.pre.split('.').next().unwrap_or_default();
+    ladder
+        .iter()
+        .position(|rung| rung == label)
+        .and_then(|position| ladder.get(position + 1))
+        .map_or(VersionIncrement::StripPrerelease, |next| {
+            VersionIncrement::Prerelease(Some(next.clone()))
+        })
+}
+
+/// Increments the trailing numeric identifier of `current` if it has the same label, otherwise starts a fresh `<label>.1`
+fn bump_prerelease(current: &semver::Prerelease, label: Option<&str>) -> semver::Prerelease {
+    let (current_label, counter) = match current.rsplit_once('.') {
+        Some((current_label, counter)) if let Ok(counter) = counter.parse::<u64>() => {
+            (current_label, Some(counter))
+        }
+        _ => match current.parse::<u64>() {
+            Ok(counter) => ("", Some(counter)),
+            Err(_) => (current.as_str(), None),
+        },
+    };
+    let label = label.unwrap_or(if current.is_empty() {
+        "pre"
+    } else {
+        current_label
+    });
+    let counter = if label == current_label {
+        counter.map_or(1, |counter| counter + 1)
+    } else {
+        1
+    };
+    let pre = if label.is_empty() {
+        counter.to_string()
+    } else {
+        format!("{label}.{counter}")
+    };
+    semver::Prerelease::new(&pre).unwrap()
+}
+
+/// Starts the label of `current` again from `1`, so `pre.3` becomes `pre.1`
+fn restart_prerelease(current: &semver::Prerelease) -> semver::Prerelease {
+    let label = match current.rsplit_once('.') {
+        Some((label, counter)) if counter.parse::<u64>().is_ok() => label,
+        _ if current.parse::<u64>().is_ok() => "",
+        _ => current.as_str(),
+    };
+    bump_prerelease(&semver::Prerelease::EMPTY, Some(label))
+}
+
+/// Increments the trailing numeric identifier of `current`, adding `build.1` if there is no build metadata or `.1` if it has no counter
+fn bump_build(current: &semver::BuildMetadata) -> semver::BuildMetadata {
+    let build = match current.rsplit_once('.') {
+        Some((rest, counter)) if let Ok(counter) = counter.parse::<u64>() => {
+            format!("{rest}.{}", counter + 1)
+        }
+        _ => match current.parse::<u64>() {
+            Ok(counter) => (counter + 1).to_string(),
+            Err(_) if current.is_empty() => "build.1".to_owned(),
+            Err(_) => format!("{current}.1"),
+        },
+    };
+    semver::BuildMetadata::new(&build).unwrap()
+}
+
+/// Picks the increment implied by Conventional Commits subjects, `feat` is minor, `fix` or anything else is patch and `!` or `BREAKING CHANGE` is major
+fn conventional_increment(subjects: &[String]) -> VersionIncrement {
+    let regex = Regex::new(r"^(?<type>\w+)(\([^)]*\))?(?<breaking>!)?:").unwrap();
+    let mut increment = VersionIncrement::Patch;
+    for subject in subjects {
+        let caps = regex.captures(subject);
+        if subject.contains("BREAKING CHANGE")
+            || caps
+                .as_ref()
+                .is_some_and(|caps| caps.name("breaking").is_some())
+        {
+            return VersionIncrement::Major;
+        }
+        if caps.is_some_and(|caps| &caps["type"] == "feat") {
+            increment = VersionIncrement::Minor;
+        }
+    }
+    increment
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+/// Styles used to highlight informational output
+#[derive(Debug, Clone, Copy)]
+enum Style {
+    Bold,
+    /// The version being replaced
+    Red,
+    /// The new version
+    Green,
+}
+
+impl Style {
+    const fn code(self) -> &'static str {
+        match self {
+            Self::Bold => "1",
+            Self::Red => "31",
+            Self::Green => "32",
+        }
+    }
+}
+
+/// How the version is written in a file, which decides how it is found and rewritten
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum FileKind {
+    /// `[package]` or `[workspace.package]` version in `Cargo.toml`
+    Cargo,
+    /// `version = "1.2.3"` in the `[project]` or `[tool.poetry]` table of `pyproject.toml`
+    Pyproject,
+    /// Top level `"version": "1.2.3"` in `package.json`, `composer.json` and `deno.json`, nested ones like `publishConfig.version` are left alone
+    ///
+    /// Comments are skipped so a commented out version in `deno.jsonc` is not mistaken for the real one
+    Json,
+    /// `version="1.2.3"` or `version='1.2.3'` in `setup.py`
+    Python,
+    /// Unquoted `version = 1.2.3` in `setup.cfg`
+    SetupCfg,
+    /// Top level `version: 1.2.3+45` in `pubspec.yaml`, the `+45` is a Flutter build number bumped separately
+    Pubspec,
+    /// `<Version>1.2.3</Version>` in .NET `.csproj` files, `<AssemblyVersion>` and `<FileVersion>` only with `--assembly-version`
+    Csproj,
+    /// `[assembly: AssemblyVersion("1.2.3.0")]` and `AssemblyFileVersion` in an `AssemblyInfo.cs`
+    ///
+    /// These only allow numbers so just the first three parts are bumped and the prerelease is left out, the fourth part is kept
+    AssemblyInfo,
+    /// `versionName "1.2.3"` in an Android `build.gradle` or `build.gradle.kts`, `versionCode` is incremented alongside it
+    Gradle,
+    /// `spec.version = "1.2.3"` in a Ruby `.gemspec`
+    Gemspec,
+    /// Top level `version: 1.2.3` in a Helm `Chart.yaml`, `appVersion` is only bumped with `--app-version`
+    HelmChart,
+    /// `const Version = "1.2.3"` in a `.go` file
+    Go,
+    /// `const VERSION: &str = "1.2.3";` or a `static` in a `.rs` file
+    Rust,
+    /// Top level `version: 1.2.3` in a `CITATION.cff`, `date-released` is set to today alongside it
+    Citation,
+    /// Unquoted `VERSION = 1.2.3` assignment in a `Makefile`, `$(VERSION)` references are left alone
+    Makefile,
+    /// The `<project><version>` of a Maven `pom.xml`, parent and dependency versions are left alone
+    Pom,
+    /// `ARG VERSION=1.2.3` or `LABEL version="1.2.3"` in a `Dockerfile`, depending on `dockerfile` in `.booper.toml`
+    Dockerfile,
+    /// The whole of `VERSION` or `version.txt`
+    Bare,
+    /// Any file found when searching, where any mention of the version is replaced
+    Loose,
+    /// Files that are never rewritten like `Cargo.lock`, which is updated separately
+    Skip,
+}
+
+impl FileKind {
+    /// Kinds that locate the version with a regex
+    const REGEX_KINDS: [Self; 17] = [
+        Self::Cargo,
+        Self::Pyproject,
+        Self::Python,
+        Self::SetupCfg,
+        Self::Pubspec,
+        Self::Csproj,
+        Self::AssemblyInfo,
+        Self::Gradle,
+        Self::Gemspec,
+        Self::HelmChart,
+        Self::Citation,
+        Self::Go,
+        Self::Rust,
+        Self::Makefile,
+        Self::Pom,
+        Self::Dockerfile,
+        Self::Loose,
+    ];
+
+    /// Works out the kind from the file name
+    fn new(path: &Path) -> Self {
+        // These are named after the project
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csproj") => return Self::Csproj,
+            Some("gemspec") => return Self::Gemspec,
+            Some("go") => return Self::Go,
+            Some("rs") => return Self::Rust,
+            _ => {}
+        }
+        match path.file_name().and_then(|name| name.to_str()) {
+            Some("Cargo.toml") => Self::Cargo,
+            Some("pyproject.toml") => Self::Pyproject,
+            Some("package.json" | "composer.json" | "deno.json" | "deno.jsonc") => Self::Json,
+            Some("setup.py") => Self::Python,
+            Some("setup.cfg") => Self::SetupCfg,
+            Some("pubspec.yaml") => Self::Pubspec,
+            Some("AssemblyInfo.cs") => Self::AssemblyInfo,
+            Some("build.gradle" | "build.gradle.kts") => Self::Gradle,
+            Some("Chart.yaml") => Self::HelmChart,
+            Some("CITATION.cff") => Self::Citation,
+            Some("Makefile" | "makefile" | "GNUmakefile") => Self::Makefile,
+            Some("pom.xml") => Self::Pom,
+            Some("Dockerfile") => Self::Dockerfile,
+            Some("VERSION" | "version.txt") => Self::Bare,
+            Some(cargo_lock::LOCK_FILE | npm_lock::LOCK_FILE | changelog::CHANGELOG_FILE) => {
+                Self::Skip
+            }
+            _ => Self::Loose,
+        }
+    }
+
+    /// Regex template where `{version}` is substituted with a pattern for the version, captured as `version`
+    ///
+    /// Line anchors use CRLF mode so Windows line endings are matched and left as they are
+    ///
+    /// A numeric `build` capture is incremented on rewrite unless the new version has its own build metadata
+    const fn template(self) -> Option<&'static str> {
+        match self {
+            // Single quotes are TOML literal strings. The key starts the line so `rust-version` and inline tables don't match
+            Self::Cargo | Self::Pyproject => {
+                Some(r#"(?m)^([ \t]*version[ \t]*=[ \t]*["'])(?<version>{version})["']"#)
+            }
+            Self::Python => Some(r#"(\bversion\s*=\s*["'])(?<version>{version})["']"#),
+            Self::SetupCfg => Some(r"(?mR)^(\s*version\s*=\s*)(?<version>{version})\s*$"),
+            Self::Pubspec => {
+                Some(r"(?mR)^(version:[ \t]*)(?<version>{version})(\+(?<build>\d+))?[ \t]*$")
+            }
+            Self::Csproj => Some(r"(<Version>\s*)(?<version>{version})\s*</Version>"),
+            Self::Pom => Some(r"(<version>\s*)(?<version>{version})\s*</version>"),
+            Self::AssemblyInfo => Some(
+                r#"(\[assembly:\s*Assembly(?:File)?Version\(\s*")(?<version>{version})(\.\d+)?"\s*\)\]"#,
+            ),
+            Self::Gemspec => Some(r#"(\b\w+\.version\s*=\s*["'])(?<version>{version})["']"#),
+            // Groovy allows leaving out the `=` and single quotes
+            Self::Gradle => Some(r#"(\bversionName\s*=?\s*["'])(?<version>{version})["']"#),
+            Self::Go => Some(r#"(\bVersion\s*=\s*")(?<version>{version})""#),
+            Self::Rust => Some(
+                r#"(\b(?:const|static)\s+VERSION\s*:\s*&\s*(?:'static\s+)?str\s*=\s*")(?<version>{version})""#,
+            ),
+            // Only an assignment at the start of a line, which covers `:=`, `?=` and `export`
+            Self::Makefile => Some(
+                r"(?mR)^([ \t]*(?:export[ \t]+)?VERSION[ \t]*[:?]?=[ \t]*)(?<version>{version})[ \t]*$",
+            ),
+            Self::HelmChart | Self::Citation => {
+                Some(r#"(?mR)^(version:[ \t]*["']?)(?<version>{version})["']?[ \t]*$"#)
+            }
+            // The `arg` and `label` captures tell which form matched
+            Self::Dockerfile => Some(
+                r#"(?mR)^((?<arg>ARG[ \t]+VERSION=)|(?<label>LABEL\b.*?\bversion="))(?<version>{version})("|[ \t]*$)"#,
+            ),
+            Self::Loose => Some(r"\b(?<version>{version})\b"),
+            Self::Json | Self::Bare | Self::Skip => None,
+        }
+    }
+
+    /// Dockerfiles are treated like any other file unless some forms are configured
+    fn configured(self, dockerfile: &[DockerfileVersion]) -> Self {
+        if self == Self::Dockerfile && dockerfile.is_empty() {
+            Self::Loose
+        } else {
+            self
+        }
+    }
+
+    /// Matches whatever version is in the file, used to find the current version
+    fn detect_regex(self) -> Option<Regex> {
+        // Any version number would match `Loose` so look for an assignment instead, which can be unquoted like `VERSION=1.2.3` in shell scripts.
+        // The key is a whole word so `NODE_VERSION=18.0.0` is not taken for the project's version
+        let template = match self {
+            Self::Loose => r#"(\b(VERSION|version) ?= ?["']?)(?<version>{version})"#,
+            _ => self.template()?,
+        };
+        let any_version = match self {
+            Self::Cargo | Self::Pyproject | Self::Python | Self::Gemspec | Self::Gradle => {
+                r#"[^"']+"#
+            }
+            Self::SetupCfg | Self::Makefile => r"\S+",
+            Self::Pubspec => r"[^\s+]+",
+            Self::Loose | Self::HelmChart | Self::Citation => r#"[^"'\s]+"#,
+            Self::Dockerfile => r#"[^"\s]+"#,
+            Self::Csproj | Self::Pom => r"[^<\s]+",
+            Self::AssemblyInfo => r"\d+\.\d+\.\d+",
+            _ => r#"[^"]+"#,
+        };
+        Some(Regex::new(&template.replace("{version}", any_version)).unwrap())
+    }
+
+    /// The part of `version` that is written to files of this kind
+    fn written_version(self, version: &str) -> &str {
+        match self {
+            Self::AssemblyInfo => version.split(['-', '+']).next().unwrap_or_default(),
+            _ => version,
+        }
+    }
+
+    /// Matches exactly one of `current`, used to rewrite it
+    fn version_regex(self, current: &[String]) -> Option<Regex> {
+        if current.is_empty() {
+            return None;
+        }
+        let template = self.template()?;
+        let mut current: Vec<String> = current
+            .iter()
+            .map(|version| regex::escape(self.written_version(version)))
+            .collect();
+        // Longest first so `1.2` does not match the start of `1.2.3`
+        current.sort_by_key(|version| std::cmp::Reverse(version.len()));
+        let pattern = format!("(?:{})", current.join("|"));
+        Some(Regex::new(&template.replace("{version}", &pattern)).unwrap())
+    }
+}
+
+/// A release, built from command line style arguments with [`clap::Parser::try_parse_from`] or by setting the fields
+#[derive(Parser)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true)]
+pub struct Booper {
+    /// Run instead of making a release
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// Can be one of `patch`, `minor`, `major`, `strip`, `pre`, `alpha`, `beta`, `rc`, `pre=<label>`, a core bump starting a prerelease like `minor-pre` or `major-rc`, `promote`, `calver`, `build` or an exact version e.g. `1.0.3`
+    ///
+    /// Defaults to `increment` in `.booper.toml`, otherwise asks which to use showing the resulting versions.
+    /// With -y / --force or --conventional it falls back to `auto` which is `patch` or `strip` for prerelease.
+    /// `auto` is always semver based, calendar versioned projects should set `increment = "calver"`.
+    /// `calver` sets `YEAR.MONTH.0` or bumps the last component if already released this month
+    pub increment: Option<VersionIncrement>,
+
+    /// Also sets `<AssemblyVersion>` and `<FileVersion>` in `.csproj` files
+    #[arg(long)]
+    pub assembly_version: bool,
+
+    /// Also bumps `appVersion` in Helm `Chart.yaml` files from its own current value
+    #[arg(long)]
+    pub app_version: bool,
+
+    /// Makes `auto` pick patch, minor or major from Conventional Commits subjects since the last tag
+    #[arg(long)]
+    pub conventional: bool,
+
+    /// Whether or not to commit the version changes
+    #[arg(short, long)]
+    pub commit: bool,
+
+    /// Whether or not to tag the commit. Requires -c / --commit
+    #[arg(short, long)]
+    pub tag: bool,
+
+    /// Whether or not to push the commit and tag. Requires -c / --commit
+    #[arg(short, long)]
+    pub push: bool,
+
+    /// Create an annotated tag instead of a lightweight one. Requires -t / --tag
+    #[arg(short, long)]
+    pub annotate: bool,
+
+    /// Reads the annotated tag message from a file, taking precedence over `tag_message`. Requires -a / --annotate
+    #[arg(long, value_name = "PATH", requires = "annotate")]
+    pub tag_message_file: Option<PathBuf>,
+
+    /// GPG sign the commit and tag
+    #[arg(short, long)]
+    pub sign: bool,
+
+    /// GPG key to sign the commit and tag with instead of git's configured default. Requires -s / --sign
+    #[arg(long, value_name = "KEYID", requires = "sign")]
+    pub signing_key: Option<String>,
+
+    /// Adds a `Signed-off-by` trailer to the release commit for projects that require the Developer Certificate of Origin
+    #[arg(long)]
+    pub signoff: bool,
+
+    /// Creates a GitHub release for the pushed tag with `gh`, using the changelog section as notes when generated
+    #[arg(long)]
+    pub github_release: bool,
+
+    /// Folds the version bump into the last commit keeping its message instead of making a new commit
+    ///
+    /// Like any commit booper makes this includes changes that are already staged
+    #[arg(long)]
+    pub amend: bool,
+
+    /// Paragraph added after the commit message, with the same placeholders as -m / --message
+    #[arg(long, value_name = "TEXT", conflicts_with = "amend")]
+    pub commit_body: Option<String>,
+
+    /// Skips the git pre-commit and commit-msg hooks for the release commit
+    #[arg(long)]
+    pub no_verify: bool,
+
+    /// Only print errors and warnings
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Print each command before it runs
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Never colour the output, which is otherwise coloured on a terminal unless `NO_COLOR` is set
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// Print a JSON summary of the release to stdout
+    #[arg(long)]
+    pub json: bool,
+
+    /// Print only the created tag name to stdout, e.g. `TAG=$(booper -ct --print-tag)`
+    #[arg(long, conflicts_with = "json")]
+    pub print_tag: bool,
+
+    /// Skips the interactive confirm step
+    #[arg(short = 'y', long)]
+    pub force: bool,
+
+    /// Shows what would change without writing, committing or pushing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Runs `cargo check` or --verify-cmd in a dry run against the changes made to a temporary copy of the repository
+    #[arg(long, requires = "dry_run", conflicts_with = "no_check")]
+    pub verify: bool,
+
+    /// Exits with 3 from a dry run when the version would change, for CI checks that a release is due
+    #[arg(long, requires = "dry_run")]
+    pub exit_nonzero_on_change: bool,
+
+    /// Keeps the prerelease label when bumping the patch, minor or major version, so `1.2.0-pre.3` becomes `1.3.0-pre.1` rather than `1.3.0`
+    #[arg(long)]
+    pub keep_pre: bool,
+
+    /// Build metadata for the new version e.g. `abc1234`, by default any existing build metadata is dropped
+    #[arg(long)]
+    pub build: Option<semver::BuildMetadata>,
+
+    /// Commit message template, `{version}`, `{from_version}` and `{tag}` are replaced. Defaults to `Version {version}`
+    #[arg(short, long, value_name = "TEMPLATE")]
+    pub message: Option<String>,
+
+    /// Tag name template where `{version}` is replaced, e.g. `release-{version}`. Defaults to the prefix of the last tag
+    #[arg(long, value_name = "TEMPLATE", global = true)]
+    pub tag_format: Option<TagFormat>,
+
+    /// Tag with the bare version without a `v` prefix
+    #[arg(long, conflicts_with = "tag_format")]
+    pub no_prefix: bool,
+
+    /// Prepend a section listing the commits since the last tag to `CHANGELOG.md`
+    #[arg(long)]
+    pub changelog: bool,
+
+    /// Groups the changelog section into Features, Fixes and Other by the Conventional Commits type of each subject
+    #[arg(long, requires = "changelog")]
+    pub changelog_from_conventional: bool,
+
+    /// Shell command to run after the version files are written but before committing, can be given multiple times
+    #[arg(long, value_name = "CMD")]
+    pub pre_hook: Vec<String>,
+
+    /// Shell command to run after committing, tagging and pushing, can be given multiple times
+    #[arg(long, value_name = "CMD")]
+    pub post_hook: Vec<String>,
+
+    /// Only release from this branch, defaults to `branch` in `.booper.toml`
+    #[arg(long)]
+    pub branch: Option<String>,
+
+    /// Release even if the current branch is not the one given by --branch
+    #[arg(long)]
+    pub force_branch: bool,
+
+    /// Remote to push the commit and tag to
+    #[arg(long, default_value = "origin")]
+    pub remote: String,
+
+    /// Times to retry a failed push, waiting a second before the first retry and doubling the wait after each
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub retry: u32,
+
+    /// Only pushes the tag, for when the branch is protected or pushed some other way
+    #[arg(long, requires = "push")]
+    pub no_push_branch: bool,
+
+    /// Pushes the commit and tag together with `git push --follow-tags`, the tag is always annotated as only those are followed
+    #[arg(long, requires = "push", conflicts_with = "no_push_branch")]
+    pub follow_tags: bool,
+
+    /// Releases the version already in the files as is, ignoring any existing tags
+    #[arg(long, conflicts_with_all = ["increment", "retag", "reconcile", "app_version", "build"])]
+    pub first_release: bool,
+
+    /// Releases a single package of a workspace, only its `Cargo.toml` and directory are updated and tags are prefixed with `<NAME>-v`
+    #[arg(long, value_name = "NAME", global = true)]
+    pub package: Option<String>,
+
+    /// Also searches the `Cargo.toml` of every `[workspace].members` crate, which must all have the same version
+    #[arg(long, conflicts_with = "package", global = true)]
+    pub workspace: bool,
+
+    /// Tags and with -p / --push pushes the version already in the files without changing or committing anything
+    #[arg(long, conflicts_with_all = ["increment", "commit", "changelog", "reconcile", "github_release"])]
+    pub retag: bool,
+
+    /// Replaces the tag if it already exists with --retag, locally and when pushing
+    #[arg(long, requires = "retag")]
+    pub force_tag: bool,
+
+    /// Just writes the exact version given to the files, skipping the last tag and `cargo check` checks, which is useful for bulk resets
+    #[arg(long, requires = "increment", conflicts_with = "verify_cmd")]
+    pub set_only: bool,
+
+    /// Skips checking that the last tag matches the version in the files, for when tags were made by hand or another tool
+    #[arg(long)]
+    pub no_tag_check: bool,
+
+    /// Bumps from this version instead of the one in the files, which are still rewritten to the result
+    #[arg(long, value_name = "VERSION", conflicts_with_all = ["first_release", "retag"])]
+    pub from: Option<Version>,
+
+    /// Allows setting a version lower than the current one without being asked
+    #[arg(long)]
+    pub allow_downgrade: bool,
+
+    /// Reads versions with missing numbers like `1.2` as `1.2.0`, the files are rewritten with the full version
+    #[arg(long)]
+    pub pad_versions: bool,
+
+    /// Bumps from the highest version when files disagree instead of failing, every file is set to the new version
+    #[arg(long)]
+    pub reconcile: bool,
+
+    /// Skips the check for uncommitted changes, any changes will be included in the version commit
+    #[arg(long)]
+    pub allow_dirty: bool,
+
+    /// Commits only the files booper changed instead of every tracked change, so with --allow-dirty other changes are left uncommitted
+    #[arg(long)]
+    pub only_version_files: bool,
+
+    /// Skips running `cargo check` after updating, `Cargo.lock` is still updated directly
+    #[arg(long)]
+    pub no_check: bool,
+
+    /// Shell command run instead of `cargo check` after updating, a failure stops the release before committing
+    #[arg(long, value_name = "CMD", conflicts_with = "no_check")]
+    pub verify_cmd: Option<String>,
+
+    /// Git executable to run, for when `git` on the PATH is not the one to use
+    #[arg(long, value_name = "PATH", env = "BOOPER_GIT", default_value = "git")]
+    pub git_bin: PathBuf,
+
+    /// Cargo executable used for `cargo check`
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "BOOPER_CARGO",
+        default_value = "cargo"
+    )]
+    pub cargo_bin: PathBuf,
+
+    /// Extra file to search for the current version, can be given multiple times
+    #[arg(
+        long = "file",
+        value_name = "PATH",
+        conflicts_with = "package",
+        global = true
+    )]
+    pub files: Vec<PathBuf>,
+}
+
+/// Subcommands other than making a release
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Prints a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+    /// Prints each file searched and the version found in it without changing anything
+    List,
+    /// Reverts the release commit at HEAD and deletes its tags, refusing if HEAD has a tag for anything else
+    Undo {
+        /// Also deletes the tag from this remote
+        #[arg(long, num_args = 0..=1, default_missing_value = "origin", value_name = "REMOTE")]
+        remote: Option<String>,
+
+        /// Skips the confirmation prompt
+        #[arg(short = 'y', long)]
+        force: bool,
+    },
+}
+
+impl Booper {
+    /// Files searched for the current version, leaving out any ignored by git as changes to them would not be committed
+    ///
+    /// With `--package` only the package's own manifest is searched. Missing default files are skipped quietly but listed ones get a warning
+    fn version_files(
+        &self,
+        git: &Git,
+        config: &Config,
+        package_manifest: Option<&Path>,
+        root: &Path,
+    ) -> Result<Vec<PathBuf>, BooperError> {
+        let mut files: Vec<PathBuf> = if let Some(package_manifest) = package_manifest {
+            vec![package_manifest.to_path_buf()]
+        } else {
+            let listed: Vec<PathBuf> = config
+                .go_version_file
+                .iter()
+                .chain(&config.rust_version_file)
+                .chain(config.offsets.iter().map(|offset| &offset.file))
+                .chain(&config.files)
+                .chain(&self.files)
+                .cloned()
+                .chain(
+                    config
+                        .matchers
+                        .iter()
+                        .map(|matcher| matcher.file().to_path_buf()),
+                )
+                .map(|file| root.join(file))
+                .collect();
+            for file in listed.iter().filter(|file| !file.exists()) {
+                eprintln!("Warning: skipping {} as it does not exist", file.display());
+            }
+            DEFAULT_FILES
+                .into_iter()
+                .map(|file| root.join(file))
+                .chain(project_files(root)?)
+                .chain((!config.dockerfile.is_empty()).then(|| root.join("Dockerfile")))
+                .chain(if self.workspace {
+                    workspace_manifests(root)
+                } else {
+                    Vec::new()
+                })
+                .chain(listed)
+                .filter(|file| file.exists())
+                .collect()
+        };
+        let ignored = git.ignored(&files)?;
+        if let Some(file) = ignored.first()
+            && config.ignored_files == IgnoredFiles::Error
+        {
+            return Err(BooperError::IgnoredVersionFile(file.clone()));
+        }
+        for file in &ignored {
+            eprintln!(
+                "Warning: skipping {} as it is ignored by git",
+                file.display()
+            );
+        }
+        files.retain(|file| !ignored.contains(file));
+        Ok(files)
+    }
+
+    /// Finds the version in the files, falling back to the last tag for tag driven projects in which case no versions are detected
+    fn current_version(
+        &self,
+        files: &[PathBuf],
+        last_tag: Option<&str>,
+        tag_format: &TagFormat,
+        config: &Config,
+        root: &Path,
+    ) -> Result<(Version, Vec<String>), BooperError> {
+        match self.find_current_version(files, config, root) {
+            Err(BooperError::NoVersionsFound) if let Some(last_tag) = last_tag => {
+                info!(
+                    self.verbosity(),
+                    "No version found in files, using the last tag {last_tag}"
+                );
+                Ok((parse_version(tag_format.strip(last_tag))?, Vec::new()))
+            }
+            result => result,
+        }
+    }
+
+    /// Finds the version shared by `files` or with `--reconcile` the highest of them, along with every version found
+    ///
+    /// Files with an `[[offset]]` count as the main version they are offset from
+    fn find_current_version(
+        &self,
+        files: &[PathBuf],
+        config: &Config,
+        root: &Path,
+    ) -> Result<(Version, Vec<String>), BooperError> {
+        let found = files
+            .iter()
+            .filter_map(|file| Some((file, detect_configured_version(file, config, root)?)))
+            .map(|(file, version)| {
+                Ok((
+                    file,
+                    self.main_version(file, version, &config.offsets, root)?,
+                ))
+            })
+            .collect::<Result<Vec<(&PathBuf, String)>, BooperError>>()?;
+        ensure(!found.is_empty(), BooperError::NoVersionsFound)?;
+        // `AssemblyInfo.cs` can't hold a prerelease so agrees with any version with the same numbers
+        let found: Vec<(&PathBuf, String)> = found
+            .iter()
+            .filter(|(file, version)| {
+                let kind = FileKind::new(file);
+                !found
+                    .iter()
+                    .any(|(_, other)| other != version && kind.written_version(other) == version)
+            })
+            .cloned()
+            .collect();
+        let mut parsed = found
+            .iter()
+            .map(|(file, version)| self.parse_file_version(file, version))
+            .collect::<Result<Vec<_>, _>>()?;
+        parsed.sort();
+        parsed.dedup();
+        let versions: Vec<String> = found.into_iter().map(|(_, version)| version).collect();
+        // Drifted files are all moved to the new version when bumping from an explicit base
+        if parsed.len() > 1 && !self.reconcile && self.from.is_none() {
+            return Err(BooperError::InconsistentVersions(versions));
+        }
+        let mut detected = versions;
+        detected.sort();
+        detected.dedup();
+        Ok((parsed.pop().unwrap(), detected))
+    }
+
+    /// Undoes the offset of `file` from the main version if it has one
+    fn main_version(
+        &self,
+        file: &Path,
+        version: String,
+        offsets: &[Offset],
+        root: &Path,
+    ) -> Result<String, BooperError> {
+        let Some(offset) = offsets
+            .iter()
+            .find(|offset| root.join(&offset.file) == file)
+        else {
+            return Ok(version);
+        };
+        let parsed = self.parse_file_version(file, &version)?;
+        let main_version = offset
+            .remove(&parsed)
+            .ok_or_else(|| BooperError::OffsetOutOfRange {
+                path: file.to_path_buf(),
+                version,
+            })?;
+        Ok(main_version.to_string())
+    }
+
+    /// Moves each `[[offset]]` file from its own version to the same distance from `to_version`
+    fn offset_versions(
+        &self,
+        config: &Config,
+        to_version: &Version,
+        root: &Path,
+    ) -> Result<Vec<(PathBuf, ToVersion)>, BooperError> {
+        let mut offset_versions = Vec::new();
+        if self.package.is_some() {
+            return Ok(offset_versions);
+        }
+        for offset in &config.offsets {
+            let path = root.join(&offset.file);
+            let Some(current) = detect_version(&path, &config.dockerfile) else {
+                continue;
+            };
+            let from_version = self.parse_file_version(&path, &current)?;
+            let offset_to_version =
+                offset
+                    .apply(to_version)
+                    .ok_or_else(|| BooperError::OffsetOutOfRange {
+                        path: path.clone(),
+                        version: to_version.to_string(),
+                    })?;
+            let offset_version = ToVersion::replacing(
+                &from_version,
+                &[current],
+                &offset_to_version,
+                &config.dockerfile,
+            );
+            offset_versions.push((path, offset_version));
+        }
+        Ok(offset_versions)
+    }
+
+    /// Parses a version found in `file`, reading `1.2` as `1.2.0` with --pad-versions
+    fn parse_file_version(&self, file: &Path, version: &str) -> Result<Version, BooperError> {
+        let padded = if self.pad_versions {
+            pad_version(version)
+        } else {
+            version.to_owned()
+        };
+        Version::parse(&padded).map_err(|source| BooperError::InvalidFileVersion {
+            path: file.to_path_buf(),
+            version: version.to_owned(),
+            source,
+        })
+    }
+
+    /// Colour is used on a terminal unless turned off with --no-color or `NO_COLOR`
+    fn color(&self) -> bool {
+        !self.no_color
+            && std::env::var_os("NO_COLOR").is_none_or(|no_color| no_color.is_empty())
+            && std::io::stderr().is_terminal()
+    }
+
+    /// Wraps `text` in the ANSI escapes for `style` when colour is on
+    fn paint(&self, style: Style, text: impl Display) -> String {
+        if self.color() {
+            format!("\x1b[{}m{text}\x1b[0m", style.code())
+        } else {
+            text.to_string()
+        }
+    }
+
+    const fn verbosity(&self) -> Verbosity {
+        if self.quiet {
+            Verbosity::Quiet
+        } else if self.verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+
+    /// Bumps the version and does whatever git operations are enabled, prompting unless `force` is set
+    pub fn run(&self) -> Result<BoopOutcome, BooperError> {
+        let verbosity = self.verbosity();
+        let git = Git::new(verbosity, self.git_bin.clone());
+        let root = repo_root(&git)?;
+        let config = Config::load(&root)?;
+        self.preflight(&git, &config)?;
+        let package_manifest = self
+            .package
+            .as_deref()
+            .map(|name| find_package_manifest(name, &root))
+            .transpose()?;
+        let package_manifest = package_manifest.as_deref();
+        // Tags from before the first release say nothing about its format
+        let last_tag = git
+            .last_tag(self.last_tag_prefix(&config).as_deref())?
+            .filter(|_| !self.first_release);
+        let tag_format = self.tag_format(&config, last_tag.as_deref());
+        let files = self.version_files(&git, &config, package_manifest, &root)?;
+        let (from_version, detected_versions) =
+            self.current_version(&files, last_tag.as_deref(), &tag_format, &config, &root)?;
+        let from_version = self.from.clone().unwrap_or(from_version);
+        if self.retag {
+            return self.retag(&git, &config, &from_version, &tag_format);
+        }
+        if let Some(last_tag) = &last_tag
+            && !self.no_tag_check
+            && !self.set_only
+        {
+            check_last_tag(&from_version, last_tag, &tag_format)?;
+        }
+        let increment = if self.first_release {
+            VersionIncrement::Exact(from_version.clone())
+        } else {
+            self.resolve_increment(&git, &config, &from_version, last_tag.as_deref())?
+        };
+        ensure(
+            !self.set_only || matches!(increment, VersionIncrement::Exact(_)),
+            BooperError::RequiresExactVersion("set-only"),
+        )?;
+        let to_version = self.to_version(&increment, &from_version);
+        if to_version < from_version && !self.allow_downgrade {
+            confirm_downgrade(&from_version, &to_version)?;
+        }
+
+        self.announce(package_manifest, &from_version, &to_version, &root);
+
+        let to_version = self.release_version(
+            &config,
+            &from_version,
+            &detected_versions,
+            &to_version,
+            &root,
+        )?;
+        let to_version_tag = self.release_tag(&git, &tag_format, &to_version)?;
+
+        let matching_files =
+            self.matching_files(&config, &to_version, package_manifest, &files, &root)?;
+        self.confirm(&git, &to_version, &matching_files, &root)?;
+        if self.dry_run {
+            if self.verify {
+                self.verify_dry_run(
+                    &git,
+                    &config,
+                    &to_version,
+                    &increment,
+                    &matching_files,
+                    &root,
+                )?;
+            }
+            return self.dry_run_outcome(to_version, to_version_tag, matching_files);
+        }
+
+        let files_changed = self.update(&config, &to_version, &increment, matching_files, &root)?;
+        let changelog_section =
+            self.update_changelog(&git, &to_version, last_tag.as_deref(), &root)?;
+        self.run_verify_step(&files_changed, &root)?;
+        for hook in config.pre_hooks.iter().chain(&self.pre_hook) {
+            run_hook(hook, &root, verbosity)?;
+        }
+        info!(verbosity, "Upgraded!");
+        // The heading duplicates the tag name and release title
+        let notes = changelog_section
+            .as_deref()
+            .and_then(|section| section.split_once("\n\n"))
+            .map(|(_, notes)| notes);
+        self.git_operations(
+            &git,
+            &config,
+            &to_version,
+            &to_version_tag,
+            &files_changed,
+            notes,
+        )?;
+        if self.github_release {
+            let remote_url = git.remote_url(&self.remote)?;
+            github::create_release(&remote_url, &to_version_tag, notes, verbosity)?;
+        }
+        for hook in config.post_hooks.iter().chain(&self.post_hook) {
+            run_hook(hook, &root, verbosity)?;
+        }
+        Ok(BoopOutcome {
+            from_version: to_version.from_string.clone(),
+            tag: (self.commit && self.tag).then_some(to_version_tag),
+            to_version: to_version.string,
+            files_changed,
+            operations: self.operations(),
+        })
+    }
+
+    /// Says which version is being released, naming the crate when there is one
+    fn announce(
+        &self,
+        package_manifest: Option<&Path>,
+        from_version: &Version,
+        to_version: &Version,
+        root: &Path,
+    ) {
+        let name = crate_name(package_manifest, root).unwrap_or_else(|| "version".to_owned());
+        if self.first_release {
+            info!(
+                self.verbosity(),
+                "Releasing {} {}",
+                self.paint(Style::Bold, &name),
+                self.paint(Style::Green, from_version)
+            );
+        } else {
+            info!(
+                self.verbosity(),
+                "Upgrading {} {} to {}",
+                self.paint(Style::Bold, &name),
+                self.paint(Style::Red, from_version),
+                self.paint(Style::Green, to_version)
+            );
+        }
+    }
+
+    /// How the files are moved from the `detected_versions` to `to_version`, including the `[[offset]]` files
+    fn release_version(
+        &self,
+        config: &Config,
+        from_version: &Version,
+        detected_versions: &[String],
+        to_version: &Version,
+        root: &Path,
+    ) -> Result<ToVersion, BooperError> {
+        // The first release keeps the version already in the files so nothing needs rewriting
+        let replaced = if self.first_release {
+            &[]
+        } else {
+            detected_versions
+        };
+        Ok(
+            ToVersion::replacing(from_version, replaced, to_version, &config.dockerfile)
+                .with_offsets(self.offset_versions(config, to_version, root)?),
+        )
+    }
+
+    /// Files to rewrite, which are those mentioning the version along with the `[[replace]]` files
+    fn matching_files(
+        &self,
+        config: &Config,
+        to_version: &ToVersion,
+        package_manifest: Option<&Path>,
+        version_files: &[PathBuf],
+        root: &Path,
+    ) -> Result<Vec<PathBuf>, BooperError> {
+        // A package only owns the files in its own directory
+        let search_root = package_manifest.and_then(Path::parent).unwrap_or(root);
+        let mut matching_files = to_version.find_files_to_update(search_root)?;
+        // Listed files in hidden directories are not reached by the search
+        for file in version_files {
+            if !matching_files.contains(file) && to_version.mentions(file) {
+                matching_files.push(file.clone());
+            }
+        }
+        // Offset files are rewritten for their own version rather than when they happen to mention this one
+        matching_files.retain(|file| to_version.offset_version(file).is_none());
+        matching_files.extend(to_version.offsets.iter().map(|(path, _)| path.clone()));
+        for file in replacement_files(&config.replacements, root)?
+            .into_iter()
+            .chain(matched_files(&config.matchers, &to_version.replaced, root))
+        {
+            if !matching_files.contains(&file) {
+                matching_files.push(file);
+            }
+        }
+        self.select_files(matching_files)
+    }
+
+    /// Runs `--verify-cmd`, or `cargo check` when a `Cargo.toml` was updated, so a broken build stops the release before committing
+    fn run_verify_step(&self, files_changed: &[PathBuf], root: &Path) -> Result<(), BooperError> {
+        let verbosity = self.verbosity();
+        if self.no_check || self.set_only {
+            return Ok(());
+        }
+        if let Some(verify_cmd) = &self.verify_cmd {
+            return run(
+                shell(verify_cmd).current_dir(root),
+                "verify command",
+                verbosity,
+            );
+        }
+        if files_changed
+            .iter()
+            .any(|file| FileKind::new(file) == FileKind::Cargo)
+        {
+            cargo_check(&self.cargo_bin, root, verbosity)?;
+        } else if verbosity == Verbosity::Verbose {
+            eprintln!("Skipping cargo check as no Cargo.toml was updated");
+        }
+        Ok(())
+    }
+
+    /// Checks that need to pass before anything is changed
+    fn preflight(&self, git: &Git, config: &Config) -> Result<(), BooperError> {
+        ensure(
+            !self.github_release || (self.commit && self.tag && self.push),
+            BooperError::RequiresPushedTag("create a GitHub release"),
+        )?;
+        if !self.allow_dirty {
+            git.assert_clean()?;
+        }
+        // Otherwise a failed push would leave a local commit and tag behind
+        if self.commit && self.push {
+            git.assert_remote_reachable(&self.remote)?;
+        }
+        if let Some(branch) = self.branch.as_ref().or(config.branch.as_ref())
+            && !self.force_branch
+        {
+            let current_branch = git.current_branch()?;
+            if &current_branch != branch {
+                return Err(BooperError::WrongBranch {
+                    expected: branch.clone(),
+                    actual: current_branch,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Picks the increment from the command line, config or `auto` which may look at the commits with `--conventional`
+    fn resolve_increment(
+        &self,
+        git: &Git,
+        config: &Config,
+        from_version: &Version,
+        last_tag: Option<&str>,
+    ) -> Result<VersionIncrement, BooperError> {
+        let increment = self.increment.clone().or_else(|| config.increment.clone());
+        let mut increment = match increment {
+            Some(increment) => increment,
+            None if !self.force && !self.conventional => self.select_increment(from_version)?,
+            None => VersionIncrement::Auto,
+        };
+        if increment == VersionIncrement::Promote && !config.promote_ladder.is_empty() {
+            increment = promote_increment(from_version, &config.promote_ladder);
+        }
+        if increment == VersionIncrement::Auto && self.conventional && from_version.pre.is_empty() {
+            increment = conventional_increment(&git.commit_subjects(last_tag)?);
+            info!(
+                self.verbosity(),
+                "Commits since {} imply a {} release",
+                last_tag.unwrap_or("the first commit"),
+                format!("{increment:?}").to_lowercase()
+            );
+        }
+        Ok(increment)
+    }
+
+    /// Asks which increment to use when none was given, showing the version each would produce
+    fn select_increment(&self, from_version: &Version) -> Result<VersionIncrement, BooperError> {
+        let mut increments = vec![
+            VersionIncrement::Patch,
+            VersionIncrement::Minor,
+            VersionIncrement::Major,
+            VersionIncrement::Prerelease(None),
+        ];
+        if !from_version.pre.is_empty() {
+            increments.insert(0, VersionIncrement::StripPrerelease);
+        }
+        let items: Vec<String> = increments
+            .iter()
+            .map(|increment| {
+                let name = match increment {
+                    VersionIncrement::StripPrerelease => "strip",
+                    VersionIncrement::Prerelease(_) => "pre",
+                    _ => &format!("{increment:?}").to_lowercase(),
+                };
+                format!("{name} → {}", self.to_version(increment, from_version))
+            })
+            .collect();
+        let selected = dialoguer::Select::new()
+            .with_prompt("Select the new version")
+            .items(&items)
+            .default(0)
+            .interact()?;
+        Ok(increments.swap_remove(selected))
+    }
+
+    fn to_version(&self, increment: &VersionIncrement, from_version: &Version) -> Version {
+        let mut to_version = increment.increment(from_version);
+        if self.keep_pre
+            && !from_version.pre.is_empty()
+            && matches!(
+                increment,
+                VersionIncrement::Patch | VersionIncrement::Minor | VersionIncrement::Major
+            )
+        {
+            to_version.pre = restart_prerelease(&from_version.pre);
+        }
+        if let Some(build) = &self.build {
+            to_version.build = build.clone();
+        }
+        to_version
+    }
+
+    /// Writes the `appVersion` changes found by [`app_versions`]
+    fn bump_app_versions(
+        &self,
+        app_versions: &[(PathBuf, Version, Version)],
+    ) -> Result<(), BooperError> {
+        for (file, from_version, to_version) in app_versions {
+            let contents = read_file(file)?;
+            let Some(current) = app_version_regex()
+                .captures(&contents)
+                .and_then(|caps| caps.name("version"))
+            else {
+                continue;
+            };
+            info!(
+                self.verbosity(),
+                "Upgrading appVersion in {} from {from_version} to {to_version}",
+                file.display()
+            );
+            let new_contents = format!(
+                "{}{to_version}{}",
+                &contents[..current.start()],
+                &contents[current.end()..]
+            );
+            write_file(file, &new_contents)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the new version to `matching_files` and everything that goes along with it, returning every changed file
+    fn update(
+        &self,
+        config: &Config,
+        to_version: &ToVersion,
+        increment: &VersionIncrement,
+        matching_files: Vec<PathBuf>,
+        root: &Path,
+    ) -> Result<Vec<PathBuf>, BooperError> {
+        let app_versions = if self.app_version {
+            app_versions(increment, &matching_files)?
+        } else {
+            Vec::new()
+        };
+        to_version.update_files(&matching_files)?;
+        update_release_dates(&matching_files)?;
+        update_version_codes(&matching_files)?;
+        to_version.apply_replacements(&config.replacements, root)?;
+        to_version.apply_matchers(&config.matchers, root)?;
+        // A `[[match]]` always writes the new version exactly where it found the old one
+        let unmatched_files: Vec<PathBuf> = matching_files
+            .iter()
+            .filter(|file| {
+                !config
+                    .matchers
+                    .iter()
+                    .any(|matcher| root.join(matcher.file()) == **file)
+            })
+            .cloned()
+            .collect();
+        to_version.check_updated(&unmatched_files)?;
+        self.bump_app_versions(&app_versions)?;
+        if self.assembly_version {
+            to_version.update_assembly_versions(&matching_files)?;
+        }
+        let lock_file = to_version.update_lock_file(&matching_files, root)?;
+        let npm_lock_file = to_version.update_npm_lock_file(&matching_files, root)?;
+        let mut files_changed = matching_files;
+        files_changed.extend(lock_file);
+        files_changed.extend(npm_lock_file);
+        if self.changelog {
+            files_changed.push(root.join(changelog::CHANGELOG_FILE));
+        }
+        Ok(files_changed)
+    }
+
+    /// Adds the commits since `last_tag` to the changelog with `--changelog`, returning the section written
+    fn update_changelog(
+        &self,
+        git: &Git,
+        to_version: &ToVersion,
+        last_tag: Option<&str>,
+        root: &Path,
+    ) -> Result<Option<String>, BooperError> {
+        if !self.changelog {
+            return Ok(None);
+        }
+        let subjects = git.commit_subjects(last_tag)?;
+        let section = changelog::section(
+            &to_version.string,
+            &subjects,
+            self.changelog_from_conventional,
+        );
+        changelog::prepend(root, &section)?;
+        Ok(Some(section))
+    }
+
+    /// Lets the user deselect files they don't want bumped unless forced
+    fn select_files(&self, files: Vec<PathBuf>) -> Result<Vec<PathBuf>, BooperError> {
+        if self.force || files.len() < 2 {
+            return Ok(files);
+        }
+        let items: Vec<String> = files
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect();
+        let selected = dialoguer::MultiSelect::new()
+            .with_prompt("Select the files to bump")
+            .items(&items)
+            .defaults(&vec![true; files.len()])
+            .interact()?;
+        Ok(files
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| selected.contains(i))
+            .map(|(_, file)| file)
+            .collect())
+    }
+
+    /// Lists what will change and asks the user to confirm unless forced
+    fn confirm(
+        &self,
+        git: &Git,
+        to_version: &ToVersion,
+        matching_files: &[PathBuf],
+        root: &Path,
+    ) -> Result<(), BooperError> {
+        let verbosity = self.verbosity();
+        if matching_files.is_empty() && !self.changelog {
+            info!(verbosity, "No files need to be changed");
+        } else {
+            info!(
+                verbosity,
+                "The following files will be changed{}:",
+                self.ops_display()
+            );
+        }
+        for file in matching_files {
+            let file_version = to_version.offset_version(file).unwrap_or(to_version);
+            match file_version.current_in(file) {
+                Some(current) => info!(
+                    verbosity,
+                    "\t{}: {} → {}",
+                    self.paint(Style::Bold, file.display()),
+                    self.paint(Style::Red, current),
+                    self.paint(Style::Green, &file_version.string)
+                ),
+                None => info!(verbosity, "\t{}", self.paint(Style::Bold, file.display())),
+            }
+        }
+        if self.changelog {
+            info!(
+                verbosity,
+                "\t{}",
+                self.paint(Style::Bold, root.join(changelog::CHANGELOG_FILE).display())
+            );
+        }
+        if self.allow_dirty
+            && self.commit
+            && !self.only_version_files
+            && git.has_uncommitted_changes()?
+        {
+            eprintln!("Warning: uncommitted changes will be included in the version commit");
+        }
+        prompt(self.force || self.dry_run)
+    }
+
+    /// Runs the verify step of `--dry-run --verify` against the changes made to a temporary copy of the files under `root`, which are never written
+    fn verify_dry_run(
+        &self,
+        git: &Git,
+        config: &Config,
+        to_version: &ToVersion,
+        increment: &VersionIncrement,
+        matching_files: &[PathBuf],
+        root: &Path,
+    ) -> Result<(), BooperError> {
+        info!(
+            self.verbosity(),
+            "Verifying the changes in a temporary copy"
+        );
+        let last_tag = git.last_tag(self.last_tag_prefix(config).as_deref())?;
+        let copy = std::env::temp_dir().join(format!("booper-verify-{}", std::process::id()));
+        let result = copy_tree(root, &copy).and_then(|()| {
+            let rebase = |file: &PathBuf| copy.join(file.strip_prefix(root).unwrap_or(file));
+            let to_version = to_version.clone().with_offsets(
+                to_version
+                    .offsets
+                    .iter()
+                    .map(|(path, offset_version)| (rebase(path), offset_version.clone()))
+                    .collect(),
+            );
+            let matching_files = matching_files.iter().map(rebase).collect();
+            let files_changed =
+                self.update(config, &to_version, increment, matching_files, &copy)?;
+            self.update_changelog(git, &to_version, last_tag.as_deref(), &copy)?;
+            self.run_verify_step(&files_changed, &copy)
+        });
+        if let Err(err) = std::fs::remove_dir_all(&copy) {
+            eprintln!(
+                "Warning: failed to remove the temporary copy {}: {err}",
+                copy.display()
+            );
+        }
+        result
+    }
+
+    /// What the release would have done for `--dry-run`, failing with `--exit-nonzero-on-change` if the version would change
+    fn dry_run_outcome(
+        &self,
+        to_version: ToVersion,
+        to_version_tag: String,
+        matching_files: Vec<PathBuf>,
+    ) -> Result<BoopOutcome, BooperError> {
+        info!(self.verbosity(), "Dry run, nothing was changed");
+        ensure(
+            !self.exit_nonzero_on_change || to_version.string == to_version.from_string,
+            BooperError::WouldRelease(to_version.string.clone()),
+        )?;
+        Ok(BoopOutcome {
+            from_version: to_version.from_string,
+            tag: (self.commit && self.tag).then_some(to_version_tag),
+            to_version: to_version.string,
+            files_changed: matching_files,
+            operations: Vec::new(),
+        })
+    }
+
+    /// Tags of a `--package` are prefixed with its name
+    fn package_tag_prefix(&self) -> Option<String> {
+        self.package.as_ref().map(|package| format!("{package}-v"))
+    }
+
+    /// Prefix the last tag is looked for with, which is the package's or a configured one
+    fn last_tag_prefix(&self, config: &Config) -> Option<String> {
+        self.package_tag_prefix()
+            .or_else(|| self.tag_format.as_ref().map(|format| format.prefix.clone()))
+            .or_else(|| config.tag_prefix.clone())
+            .filter(|prefix| !prefix.is_empty())
+    }
+
+    /// The tag for `to_version`, which can't exist yet if it is going to be created
+    fn release_tag(
+        &self,
+        git: &Git,
+        tag_format: &TagFormat,
+        to_version: &ToVersion,
+    ) -> Result<String, BooperError> {
+        let tag = tag_format.format(&to_version.string);
+        if self.tag && git.tag_exists(&tag)? {
+            return Err(BooperError::TagExists(tag));
+        }
+        Ok(tag)
+    }
+
+    fn tag_format(&self, config: &Config, last_tag: Option<&str>) -> TagFormat {
+        self.tag_format
+            .clone()
+            .or_else(|| {
+                self.package_tag_prefix().map(|prefix| TagFormat {
+                    prefix,
+                    suffix: String::new(),
+                })
+            })
+            .or_else(|| self.no_prefix.then(TagFormat::default))
+            .or_else(|| {
+                config.tag_prefix.clone().map(|prefix| TagFormat {
+                    prefix,
+                    suffix: String::new(),
+                })
+            })
+            .unwrap_or_else(|| {
+                if config.go_version_file.is_some() {
+                    TagFormat::infer(None)
+                } else {
+                    TagFormat::infer(last_tag)
+                }
+            })
+    }
+
+    /// Lists the git operations that will run e.g. `, committed and tagged`
+    fn operations(&self) -> Vec<&'static str> {
+        let mut ops = Vec::new();
+        if self.commit {
+            ops.push("committed");
+            if self.tag {
+                ops.push("tagged");
+            }
+            if self.push {
+                ops.push("pushed");
+            }
+            if self.github_release && self.tag && self.push {
+                ops.push("released");
+            }
+        }
+        ops
+    }
+
+    fn ops_display(&self) -> String {
+        let mut ops = self.operations();
+        if let Some(last) = ops.pop() {
+            let mut output = String::new();
+            for x in ops {
+                let _ = write!(output, ", {x}");
+            }
+            let _ = write!(output, " and {last}");
+            output
+        } else {
+            String::new()
+        }
+    }
+
+    fn git_operations(
+        &self,
+        git: &Git,
+        config: &Config,
+        to_version: &ToVersion,
+        to_version_tag: &str,
+        files_changed: &[PathBuf],
+        notes: Option<&str>,
+    ) -> Result<(), BooperError> {
+        if self.commit {
+            let msg = self.commit_message(config, to_version, to_version_tag);
+            if self.only_version_files {
+                for file in files_changed {
+                    git.stage(file)?;
+                }
+            } else if self.changelog {
+                // The changelog may be new so is not picked up by `git commit -a`
+                for file in files_changed.iter().filter(|file| {
+                    file.file_name()
+                        .is_some_and(|name| name == changelog::CHANGELOG_FILE)
+                }) {
+                    git.stage(file)?;
+                }
+            }
+            let body = self
+                .commit_body
+                .as_deref()
+                .map(|body| to_version.render(body, to_version_tag));
+            git.commit(
+                &msg,
+                body.as_deref(),
+                CommitOptions {
+                    sign: self.sign,
+                    signing_key: self.signing_key.as_deref(),
+                    signoff: self.signoff,
+                    no_verify: self.no_verify,
+                    amend: self.amend,
+                    allow_empty: files_changed.is_empty(),
+                    all: !self.only_version_files,
+                },
+            )?;
+            if self.push && !self.no_push_branch && !self.pushes_together() {
+                git.push(&self.remote, self.retry)?;
+            }
+
+            if self.tag {
+                self.create_tag(git, config, to_version, to_version_tag, &msg, notes)?;
+            }
+            if self.pushes_together() {
+                git.push_follow_tags(&self.remote, self.retry)?;
+            }
+        } else {
+            ensure(!self.tag, BooperError::RequiresCommit("tag"))?;
+            ensure(!self.annotate, BooperError::RequiresCommit("annotate"))?;
+            ensure(!self.amend, BooperError::RequiresCommit("amend"))?;
+            ensure(!self.signoff, BooperError::RequiresCommit("sign off"))?;
+            ensure(!self.push, BooperError::RequiresCommit("push"))?;
+        }
+        Ok(())
+    }
+
+    /// Whether the commit and tag go in a single `--follow-tags` push rather than one each
+    const fn pushes_together(&self) -> bool {
+        self.follow_tags && self.commit && self.tag && self.push
+    }
+
+    fn commit_message(
+        &self,
+        config: &Config,
+        to_version: &ToVersion,
+        to_version_tag: &str,
+    ) -> String {
+        let template = self
+            .message
+            .as_deref()
+            .or(config.commit_message.as_deref())
+            .unwrap_or(DEFAULT_COMMIT_MESSAGE);
+        to_version.render(template, to_version_tag)
+    }
+
+    /// Tags and optionally pushes the tag, `commit_message` is the tag message if annotating without a `tag_message` template
+    ///
+    /// With --changelog the new section follows the tag message
+    fn create_tag(
+        &self,
+        git: &Git,
+        config: &Config,
+        to_version: &ToVersion,
+        to_version_tag: &str,
+        commit_message: &str,
+        changelog_notes: Option<&str>,
+    ) -> Result<(), BooperError> {
+        // Signed tags are always annotated, as are followed tags since `--follow-tags` skips lightweight ones
+        let tag_message = (self.annotate || self.sign || self.pushes_together()).then(|| {
+            let message = config.tag_message.as_deref().map_or_else(
+                || commit_message.to_owned(),
+                |template| to_version.render(template, to_version_tag),
+            );
+            match changelog_notes {
+                Some(notes) => format!("{message}\n\n{notes}"),
+                None => message,
+            }
+        });
+        let tag_message = self
+            .tag_message_file
+            .as_deref()
+            .map(TagMessage::File)
+            .or_else(|| tag_message.as_deref().map(TagMessage::Inline));
+        git.tag(
+            to_version_tag,
+            tag_message,
+            self.sign,
+            self.signing_key.as_deref(),
+        )?;
+        if self.push && !self.pushes_together() {
+            git.push_tag(&self.remote, to_version_tag, self.force_tag, self.retry)?;
+        }
+        Ok(())
+    }
+
+    /// Tags the version already in the files for `--retag`, nothing is changed or committed
+    fn retag(
+        &self,
+        git: &Git,
+        config: &Config,
+        version: &Version,
+        tag_format: &TagFormat,
+    ) -> Result<BoopOutcome, BooperError> {
+        let verbosity = self.verbosity();
+        let to_version =
+            ToVersion::replacing(version, &[version.to_string()], version, &config.dockerfile);
+        let to_version_tag = tag_format.format(&to_version.string);
+        let tag_exists = git.tag_exists(&to_version_tag)?;
+        if tag_exists && !self.force_tag {
+            return Err(BooperError::TagExists(to_version_tag));
+        }
+        info!(
+            verbosity,
+            "Tagging version {version} as {to_version_tag}{}{}",
+            if tag_exists {
+                ", replacing the existing tag"
+            } else {
+                ""
+            },
+            if self.push { " and pushing it" } else { "" }
+        );
+        if self.dry_run {
+            info!(verbosity, "Dry run, nothing was changed");
+            // Retagging always releases the version so there is always a change
+            ensure(
+                !self.exit_nonzero_on_change,
+                BooperError::WouldRelease(to_version.string.clone()),
+            )?;
+            return Ok(BoopOutcome {
+                from_version: to_version.from_string,
+                to_version: to_version.string,
+                tag: Some(to_version_tag),
+                files_changed: Vec::new(),
+                operations: Vec::new(),
+            });
+        }
+        prompt(self.force)?;
+        if tag_exists {
+            git.delete_tag(&to_version_tag)?;
+        }
+        let msg = self.commit_message(config, &to_version, &to_version_tag);
+        self.create_tag(git, config, &to_version, &to_version_tag, &msg, None)?;
+        info!(verbosity, "Tagged!");
+        let mut operations = vec!["tagged"];
+        if self.push {
+            operations.push("pushed");
+        }
+        Ok(BoopOutcome {
+            from_version: to_version.from_string.clone(),
+            to_version: to_version.string,
+            tag: Some(to_version_tag),
+            files_changed: Vec::new(),
+            operations,
+        })
+    }
+
+    /// Prints the version detected in each file searched for `booper list` and whether they agree
+    pub fn list(&self) -> Result<(), BooperError> {
+        let git = Git::new(self.verbosity(), self.git_bin.clone());
+        let root = repo_root(&git)?;
+        let config = Config::load(&root)?;
+        let package_manifest = self
+            .package
+            .as_deref()
+            .map(|name| find_package_manifest(name, &root))
+            .transpose()?;
+        let files = self.version_files(&git, &config, package_manifest.as_deref(), &root)?;
+        let mut versions = Vec::new();
+        for file in &files {
+            if let Some(version) = detect_configured_version(file, &config, &root) {
+                let main_version =
+                    self.main_version(file, version.clone(), &config.offsets, &root)?;
+                if main_version == version {
+                    println!("{}: {version}", file.display());
+                } else {
+                    println!("{}: {version}, offset from {main_version}", file.display());
+                }
+                versions.push(main_version);
+            } else {
+                println!("{}: no version", file.display());
+            }
+        }
+        versions.sort();
+        versions.dedup();
+        match versions.as_slice() {
+            [] => println!("No versions found"),
+            [version] => println!("Consistent version {version}"),
+            versions => println!("Inconsistent versions {}", versions.join(", ")),
+        }
+        Ok(())
+    }
+
+    /// Resets away the release commit at HEAD for `booper undo`, checking it looks like one first
+    pub fn undo(&self, remote: Option<&str>, force: bool) -> Result<(), BooperError> {
+        let verbosity = self.verbosity();
+        let git = Git::new(verbosity, self.git_bin.clone());
+        let root = repo_root(&git)?;
+        let config = Config::load(&root)?;
+        ensure(
+            !git.has_uncommitted_changes()?,
+            BooperError::UncommittedChanges,
+        )?;
+        let package_manifest = self
+            .package
+            .as_deref()
+            .map(|name| find_package_manifest(name, &root))
+            .transpose()?;
+        let files = self.version_files(&git, &config, package_manifest.as_deref(), &root)?;
+        let (version, _) = self.find_current_version(&files, &config, &root)?;
+        let version = version.to_string();
+        let tag = self
+            .tag_format(
+                &config,
+                git.last_tag(self.last_tag_prefix(&config).as_deref())?
+                    .as_deref(),
+            )
+            .format(&version);
+        let message = git.head_message()?;
+        let template = config
+            .commit_message
+            .as_deref()
+            .unwrap_or(DEFAULT_COMMIT_MESSAGE);
+        if !release_message_regex(template, &version, &tag).is_match(&message) {
+            return Err(BooperError::NotReleaseCommit { message, version });
+        }
+        // Whatever format the release was tagged with its tags name the version
+        let (tags, unknown_tags): (Vec<String>, Vec<String>) = git
+            .tags_at_head()?
+            .into_iter()
+            .partition(|tag| tag_names_version(tag, &version));
+        ensure(
+            unknown_tags.is_empty(),
+            BooperError::UnknownTags(unknown_tags),
+        )?;
+
+        info!(verbosity, "Undoing the release of {version}, this will:");
+        info!(verbosity, "\treset the commit {message:?}");
+        for tag in &tags {
+            info!(verbosity, "\tdelete the tag {tag}");
+            if let Some(remote) = remote {
+                info!(verbosity, "\tdelete the tag {tag} from {remote}");
+            }
+        }
+        prompt(force)?;
+
+        for tag in &tags {
+            if let Some(remote) = remote {
+                git.delete_remote_tag(remote, tag)?;
+            }
+            git.delete_tag(tag)?;
+        }
+        git.reset_hard("HEAD~1")?;
+        info!(verbosity, "Undone!");
+        Ok(())
+    }
+}
+
+/// Summary of a release printed by `--json`
+#[derive(Debug, Serialize)]
+pub struct BoopOutcome {
+    /// The version before the release
+    pub from_version: String,
+    /// The released version
+    pub to_version: String,
+    /// Only set when a tag was created
+    pub tag: Option<String>,
+    /// Every file written, including the lock file and changelog, or with `--dry-run` the files that would be
+    pub files_changed: Vec<PathBuf>,
+    /// Which of `committed`, `tagged`, `pushed` and `released` happened
+    pub operations: Vec<&'static str>,
+}
+
+/// Text around the version in a tag name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFormat {
+    prefix: String,
+    suffix: String,
+}
+
+impl FromStr for TagFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (prefix, suffix) = s
+            .split_once("{version}")
+            .ok_or_else(|| "tag format must contain {version}".to_owned())?;
+        Ok(Self {
+            prefix: prefix.to_owned(),
+            suffix: suffix.to_owned(),
+        })
+    }
+}
+
+impl TagFormat {
+    /// Follows the `v` prefix convention of the last tag, using it when there are no tags yet
+    fn infer(last_tag: Option<&str>) -> Self {
+        let prefix = match last_tag {
+            Some(last_tag) if !last_tag.starts_with('v') => "",
+            _ => "v",
+        };
+        Self {
+            prefix: prefix.to_owned(),
+            suffix: String::new(),
+        }
+    }
+
+    fn format(&self, version: &str) -> String {
+        format!("{}{version}{}", self.prefix, self.suffix)
+    }
+
+    /// Extracts the version from a tag, returning the tag unchanged if it does not match the format
+    fn strip<'a>(&self, tag: &'a str) -> &'a str {
+        tag.strip_prefix(self.prefix.as_str())
+            .and_then(|tag| tag.strip_suffix(self.suffix.as_str()))
+            .unwrap_or(tag)
+    }
+}
+
+/// The new version and how to find and rewrite the old one
+#[derive(Clone)]
+pub struct ToVersion {
+    from_string: String,
+    string: String,
+    /// Versions that get replaced, more than one when reconciling
+    replaced: Vec<String>,
+    /// Matches the replaced versions for each of [`FileKind::REGEX_KINDS`]
+    regexes: HashMap<FileKind, Regex>,
+    /// Forms of the version bumped in a `Dockerfile`
+    dockerfile: Vec<DockerfileVersion>,
+    /// How each `[[offset]]` file is moved instead
+    offsets: Vec<(PathBuf, Self)>,
+}
+
+impl ToVersion {
+    /// Moves the files from `from_version` to `to_version`
+    pub fn new(from_version: &Version, to_version: &Version) -> Self {
+        Self::replacing(from_version, &[from_version.to_string()], to_version, &[])
+    }
+
+    /// Moves the files from any of the `replaced` versions to `to_version` along with the `dockerfile` forms, `from_version` is only used in messages
+    fn replacing(
+        from_version: &Version,
+        replaced: &[String],
+        to_version: &Version,
+        dockerfile: &[DockerfileVersion],
+    ) -> Self {
+        let regexes = FileKind::REGEX_KINDS
+            .into_iter()
+            .filter_map(|kind| Some((kind, kind.version_regex(replaced)?)))
+            .collect();
+        Self {
+            from_string: from_version.to_string(),
+            string: to_version.to_string(),
+            replaced: replaced.to_vec(),
+            regexes,
+            dockerfile: dockerfile.to_vec(),
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Moves these files to their own versions instead
+    fn with_offsets(self, offsets: Vec<(PathBuf, Self)>) -> Self {
+        Self { offsets, ..self }
+    }
+
+    /// How `file` is moved if it has an `[[offset]]`
+    fn offset_version(&self, file: &Path) -> Option<&Self> {
+        self.offsets
+            .iter()
+            .find(|(path, _)| path == file)
+            .map(|(_, offset_version)| offset_version)
+    }
+
+    /// Whether `version` is one of the versions being replaced
+    fn replaces(&self, version: &str) -> bool {
+        self.replaced.iter().any(|replaced| replaced == version)
+    }
+
+    /// Fills in the `{version}`, `{from_version}` and `{tag}` placeholders of a message template
+    fn render(&self, template: &str, to_version_tag: &str) -> String {
+        template
+            .replace("{version}", &self.string)
+            .replace("{from_version}", &self.from_string)
+            .replace("{tag}", to_version_tag)
+    }
+
+    /// Files under `root` that mention one of the replaced versions in a way booper knows how to rewrite
+    ///
+    /// Hidden directories like `.github` are not searched as they hold tool and action versions that can happen to equal the project's,
+    /// the only hidden files searched are default version files like `.env`
+    pub fn find_files_to_update(&self, root: &Path) -> Result<Vec<PathBuf>, BooperError> {
+        ignore::WalkBuilder::new(root)
+            .hidden(false)
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                !name.starts_with('.')
+                    || entry.depth() == 0
+                    || (entry
+                        .file_type()
+                        .is_some_and(|file_type| file_type.is_file())
+                        && DEFAULT_FILES.contains(&name.as_ref()))
+            })
+            .build()
+            .filter_map(|entry| {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(err) => return Some(Err(err.into())),
+                };
+                let file = entry.path();
+                (entry.file_type()?.is_file() && self.mentions(file))
+                    .then(|| Ok(file.to_path_buf()))
+            })
+            .collect()
+    }
+
+    /// Whether `file` has one of the replaced versions in a way booper knows how to rewrite
+    fn mentions(&self, file: &Path) -> bool {
+        let kind = FileKind::new(file).configured(&self.dockerfile);
+        if kind == FileKind::Skip {
+            return false;
+        }
+        // Binary or unreadable files can't contain a version we know how to replace
+        let Ok(contents) = std::fs::read_to_string(file) else {
+            return false;
+        };
+        match kind {
+            FileKind::Bare => self.replaces(contents.trim()),
+            FileKind::Json => {
+                json_version(&contents).is_some_and(|range| self.replaces(&contents[range]))
+            }
+            _ => self.regexes.get(&kind).is_some_and(|regex| {
+                regex
+                    .captures_iter(&contents)
+                    .any(|caps| is_version_match(kind, &contents, &caps, &self.dockerfile))
+            }),
+        }
+    }
+
+    /// Which of the replaced versions `file` has, unknown for `[[replace]]` files
+    fn current_in(&self, file: &Path) -> Option<String> {
+        if let Some(offset_version) = self.offset_version(file) {
+            return offset_version.current_in(file);
+        }
+        let contents = std::fs::read_to_string(file).ok()?;
+        let kind = FileKind::new(file).configured(&self.dockerfile);
+        match kind {
+            FileKind::Bare => return Some(contents.trim().to_owned()),
+            FileKind::Json => return Some(contents[json_version(&contents)?].to_owned()),
+            _ => {}
+        }
+        let cap = self
+            .regexes
+            .get(&kind)?
+            .captures_iter(&contents)
+            .find(|caps| is_version_match(kind, &contents, caps, &self.dockerfile))?;
+        Some(cap.name("version")?.as_str().to_owned())
+    }
+
+    /// Rewrites the version in each of `matching_files`
+    pub fn update_files(&self, matching_files: &[PathBuf]) -> Result<(), BooperError> {
+        for file in matching_files {
+            if let Some(offset_version) = self.offset_version(file) {
+                offset_version.update_files(std::slice::from_ref(file))?;
+                continue;
+            }
+            let kind = FileKind::new(file).configured(&self.dockerfile);
+            if kind == FileKind::Bare {
+                let newline = line_ending(&read_file(file)?);
+                write_file(file, &format!("{}{newline}", self.string))?;
+                continue;
+            }
+            if kind == FileKind::Json {
+                let mut contents = read_file(file)?;
+                if let Some(range) = json_version(&contents)
+                    && self.replaces(&contents[range.clone()])
+                {
+                    contents.replace_range(range, &self.string);
+                    write_file(file, &contents)?;
+                }
+                continue;
+            }
+            let Some(regex) = self.regexes.get(&kind) else {
+                continue;
+            };
+            let contents = read_file(file)?;
+            let replaced_contents = regex.replace_all(&contents, |caps: &Captures| {
+                if is_version_match(kind, &contents, caps, &self.dockerfile) {
+                    replace_captured(caps, kind.written_version(&self.string))
+                } else {
+                    caps[0].to_owned()
+                }
+            });
+            write_file(file, replaced_contents.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Re-reads `files` to make sure the rewrite actually produced the new version
+    pub fn check_updated(&self, files: &[PathBuf]) -> Result<(), BooperError> {
+        let new_version = [self.string.clone()];
+        for file in files {
+            if let Some(offset_version) = self.offset_version(file) {
+                offset_version.check_updated(std::slice::from_ref(file))?;
+                continue;
+            }
+            let contents = read_file(file)?;
+            let kind = FileKind::new(file).configured(&self.dockerfile);
+            let updated = match kind {
+                FileKind::Bare => contents.trim() == self.string,
+                FileKind::Json => {
+                    json_version(&contents).is_some_and(|range| contents[range] == self.string)
+                }
+                _ => kind.version_regex(&new_version).is_none_or(|regex| {
+                    regex
+                        .captures_iter(&contents)
+                        .any(|caps| is_version_match(kind, &contents, &caps, &self.dockerfile))
+                }),
+            };
+            ensure(updated, BooperError::NotUpdated(file.clone()))?;
+        }
+        Ok(())
+    }
+
+    /// Applies the `[[replace]]` patterns from `.booper.toml`
+    fn apply_replacements(
+        &self,
+        replacements: &[Replacement],
+        root: &Path,
+    ) -> Result<(), BooperError> {
+        let version_number =
+            Regex::new(r"\d+\.\d+\.\d+(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?").unwrap();
+        for replacement in replacements {
+            let path = root.join(&replacement.file);
+            let contents = read_file(&path)?;
+            let replaced_contents =
+                replacement
+                    .pattern
+                    .replace_all(&contents, |caps: &Captures| {
+                        if caps.name("version").is_some() {
+                            replace_captured(caps, &self.string)
+                        } else {
+                            version_number
+                                .replace(&caps[0], self.string.as_str())
+                                .into_owned()
+                        }
+                    });
+            write_file(&path, replaced_contents.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Rewrites the version found by each `[[match]]` if it is one being replaced
+    fn apply_matchers(&self, matchers: &[Matcher], root: &Path) -> Result<(), BooperError> {
+        for matcher in matchers {
+            let path = root.join(matcher.file());
+            if !path.exists() {
+                continue;
+            }
+            let contents = read_file(&path)?;
+            if let Some(range) = matcher.find(&contents)
+                && self
+                    .replaced
+                    .iter()
+                    .any(|version| *version == contents[range.clone()])
+            {
+                let mut new_contents = contents;
+                new_contents.replace_range(range, &self.string);
+                write_file(&path, &new_contents)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets `<AssemblyVersion>` and `<FileVersion>` in the `.csproj` files being updated, which only allow numbers so the prerelease and build are dropped
+    fn update_assembly_versions(&self, matching_files: &[PathBuf]) -> Result<(), BooperError> {
+        let regex = Regex::new(
+            r"<(?<element>AssemblyVersion|FileVersion)>\s*(?<version>[^<\s]+)\s*</(AssemblyVersion|FileVersion)>",
+        )
+        .unwrap();
+        let core = self.string.split(['-', '+']).next().unwrap_or_default();
+        for file in matching_files {
+            if FileKind::new(file) != FileKind::Csproj {
+                continue;
+            }
+            let contents = read_file(file)?;
+            let replaced_contents = regex.replace_all(&contents, |caps: &Captures| {
+                // Keep the four part `1.2.3.0` form if it is already used
+                let revision = if caps["version"].split('.').count() == 4 {
+                    ".0"
+                } else {
+                    ""
+                };
+                let element = &caps["element"];
+                format!("<{element}>{core}{revision}</{element}>")
+            });
+            write_file(file, replaced_contents.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Bumps the packages of the updated manifests in `Cargo.lock`, returning its path if it changed
+    fn update_lock_file(
+        &self,
+        matching_files: &[PathBuf],
+        root: &Path,
+    ) -> Result<Option<PathBuf>, BooperError> {
+        let mut packages = Vec::new();
+        for file in matching_files {
+            if FileKind::new(file) == FileKind::Cargo
+                && self.offset_version(file).is_none()
+                && let Some(name) = cargo_package_name(&read_file(file)?)
+            {
+                packages.push(name);
+            }
+        }
+        cargo_lock::update(root, &packages, &self.replaced, &self.string)
+    }
+
+    /// Bumps the root package in `package-lock.json` when `package.json` is updated, returning its path if it changed
+    fn update_npm_lock_file(
+        &self,
+        matching_files: &[PathBuf],
+        root: &Path,
+    ) -> Result<Option<PathBuf>, BooperError> {
+        if !matching_files.contains(&root.join("package.json")) {
+            return Ok(None);
+        }
+        npm_lock::update(root, &self.replaced, &self.string)
+    }
+}
+
+/// Files with a match for one of the `[[replace]]` patterns
+fn replacement_files(
+    replacements: &[Replacement],
+    root: &Path,
+) -> Result<Vec<PathBuf>, BooperError> {
+    let mut files = Vec::new();
+    for replacement in replacements {
+        let path = root.join(&replacement.file);
+        if replacement.pattern.is_match(&read_file(&path)?) && !files.contains(&path) {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Files of the `[[match]]` entries that have one of the `replaced` versions
+fn matched_files(matchers: &[Matcher], replaced: &[String], root: &Path) -> Vec<PathBuf> {
+    matchers
+        .iter()
+        .map(|matcher| (matcher, root.join(matcher.file())))
+        .filter(|(matcher, path)| {
+            std::fs::read_to_string(path).is_ok_and(|contents| {
+                matcher.find(&contents).is_some_and(|range| {
+                    replaced
+                        .iter()
+                        .any(|version| *version == contents[range.clone()])
+                })
+            })
+        })
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// Name of the crate in `package_manifest` or the `Cargo.toml` at `root`
+fn crate_name(package_manifest: Option<&Path>, root: &Path) -> Option<String> {
+    let manifest = package_manifest.map_or_else(|| root.join("Cargo.toml"), Path::to_path_buf);
+    cargo_package_name(&std::fs::read_to_string(manifest).ok()?)
+}
+
+/// Finds the `Cargo.toml` of the package called `name` under `root`
+fn find_package_manifest(name: &str, root: &Path) -> Result<PathBuf, BooperError> {
+    for entry in ignore::Walk::new(root) {
+        let path = entry?.into_path();
+        if path
+            .file_name()
+            .is_some_and(|file_name| file_name == "Cargo.toml")
+            && cargo_package_name(&read_file(&path)?).as_deref() == Some(name)
+        {
+            return Ok(path);
+        }
+    }
+    Err(BooperError::PackageNotFound(name.to_owned()))
+}
+
+/// The `Cargo.toml` of each `[workspace].members` entry in the `Cargo.toml` at `root`, expanding `*` and `?` wildcards
+fn workspace_manifests(root: &Path) -> Vec<PathBuf> {
+    let Some(manifest) = std::fs::read_to_string(root.join("Cargo.toml"))
+        .ok()
+        .and_then(|contents| contents.parse::<toml::Table>().ok())
+    else {
+        return Vec::new();
+    };
+    let Some(members) = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(toml::Value::as_array)
+    else {
+        return Vec::new();
+    };
+    let mut manifests: Vec<PathBuf> = members
+        .iter()
+        .filter_map(toml::Value::as_str)
+        .flat_map(|member| expand_member(member, root))
+        .map(|member| member.join("Cargo.toml"))
+        .collect();
+    manifests.sort();
+    manifests.dedup();
+    manifests
+}
+
+/// Directories under `root` matching a workspace member path which may contain wildcards in any component
+fn expand_member(pattern: &str, root: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![root.to_path_buf()];
+    for component in pattern.split('/').filter(|component| !component.is_empty()) {
+        if !component.contains(['*', '?']) {
+            for path in &mut paths {
+                path.push(component);
+            }
+            continue;
+        }
+        let regex = Regex::new(&format!(
+            "^{}$",
+            regex::escape(component)
+                .replace(r"\*", ".*")
+                .replace(r"\?", ".")
+        ))
+        .unwrap();
+        paths = paths
+            .iter()
+            .filter_map(|path| std::fs::read_dir(path).ok())
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let is_match =
+                    entry.file_type().ok()?.is_dir() && regex.is_match(entry.file_name().to_str()?);
+                is_match.then(|| entry.path())
+            })
+            .collect();
+    }
+    paths
+}
+
+/// The root of the repository, which every file is searched from and resolved against so the ones rewritten are all the ones being committed
+///
+/// It is `.` unless run from a subdirectory, which gets a warning, then the `..` needed to get back up. The current directory is never changed
+/// as that would affect the rest of a program using booper as a library
+fn repo_root(git: &Git) -> Result<PathBuf, BooperError> {
+    let current = PathBuf::from(".");
+    let Some(root) = git.toplevel()? else {
+        return Ok(current);
+    };
+    let root = root.canonicalize().unwrap_or(root);
+    let Ok(cwd) = std::env::current_dir().and_then(std::fs::canonicalize) else {
+        return Ok(current);
+    };
+    if cwd == root {
+        return Ok(current);
+    }
+    eprintln!(
+        "Warning: running from {} inside the repository, files are searched from its root {}",
+        cwd.display(),
+        root.display()
+    );
+    Ok(cwd.strip_prefix(&root).map_or_else(
+        |_| root.clone(),
+        |below| below.components().map(|_| Component::ParentDir).collect(),
+    ))
+}
+
+/// Whether `tag` has `version` in it and not just the start of a longer version like `1.2.30` or `1.2.3-rc.1`
+fn tag_names_version(tag: &str, version: &str) -> bool {
+    tag.match_indices(version).any(|(start, _)| {
+        let before = tag[..start].chars().next_back();
+        let after = tag[start + version.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_digit() || c == '.')
+            && !after.is_some_and(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'))
+    })
+}
+
+/// Copies the files under `root` to `dest`, leaving out `.git` and anything it ignores like build output
+fn copy_tree(root: &Path, dest: &Path) -> Result<(), BooperError> {
+    if dest.exists() {
+        std::fs::remove_dir_all(dest).map_err(|source| BooperError::File {
+            path: dest.to_path_buf(),
+            source,
+        })?;
+    }
+    for entry in ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+    {
+        let entry = entry?;
+        let path = entry.path();
+        let target = dest.join(path.strip_prefix(root).unwrap_or(path));
+        let copied = if entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_dir())
+        {
+            std::fs::create_dir_all(&target)
+        } else {
+            std::fs::copy(path, &target).map(drop)
+        };
+        copied.map_err(|source| BooperError::File {
+            path: target,
+            source,
+        })?;
+    }
+    Ok(())
+}
+
+/// Files named after the project so they can't be listed in [`DEFAULT_FILES`]
+///
+/// `.csproj` files are searched for anywhere that is not ignored as .NET projects usually keep them in `src/<Name>/`, `.gemspec` files only
+/// at `root`
+fn project_files(root: &Path) -> Result<Vec<PathBuf>, BooperError> {
+    let mut files = Vec::new();
+    for entry in ignore::Walk::new(root) {
+        let entry = entry?;
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let kind = FileKind::new(entry.path());
+        if kind == FileKind::Csproj || (kind == FileKind::Gemspec && entry.depth() == 1) {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Rewrites a match of one of [`ToVersion::regexes`] with `new_version`
+fn replace_captured(caps: &Captures, new_version: &str) -> String {
+    let whole = caps.get_match();
+    let version = caps.name("version").unwrap();
+    let build = caps.name("build");
+    let prefix = &whole.as_str()[..version.start() - whole.start()];
+    let suffix_start = build.map_or_else(|| version.end(), |build| build.end());
+    let suffix = &whole.as_str()[suffix_start - whole.start()..];
+    let build = match build {
+        // Build metadata on the new version replaces the build number
+        Some(build) if !new_version.contains('+') => {
+            let number = build.as_str().parse::<u64>().unwrap_or_default();
+            format!("+{}", number + 1)
+        }
+        _ => String::new(),
+    };
+    format!("{prefix}{new_version}{build}{suffix}")
+}
+
+/// Matches the version in a Helm chart's `appVersion`, leaving out a `v` prefix
+fn app_version_regex() -> Regex {
+    Regex::new(r#"(?m)^appVersion:[ \t]*["']?v?(?<version>[^"'\s]+)"#).unwrap()
+}
+
+/// The current and bumped `appVersion` of the Helm charts being updated for `--app-version`, independently of the chart version
+///
+/// Found before anything is written so an `appVersion` that isn't semver stops the release without leaving it half done
+fn app_versions(
+    increment: &VersionIncrement,
+    matching_files: &[PathBuf],
+) -> Result<Vec<(PathBuf, Version, Version)>, BooperError> {
+    let mut app_versions = Vec::new();
+    for file in matching_files {
+        if FileKind::new(file) != FileKind::HelmChart {
+            continue;
+        }
+        let contents = read_file(file)?;
+        let Some(current) = app_version_regex()
+            .captures(&contents)
+            .and_then(|caps| caps.name("version"))
+        else {
+            continue;
+        };
+        let from_version = parse_version(current.as_str())?;
+        let to_version = increment.increment(&from_version);
+        app_versions.push((file.clone(), from_version, to_version));
+    }
+    Ok(app_versions)
+}
+
+/// Increments the integer `versionCode` in the `build.gradle` files being updated, if they have one
+fn update_version_codes(matching_files: &[PathBuf]) -> Result<(), BooperError> {
+    let regex = Regex::new(r"\b(?<key>versionCode\s*=?\s*)(?<code>\d+)\b").unwrap();
+    for file in matching_files {
+        if FileKind::new(file) != FileKind::Gradle {
+            continue;
+        }
+        let contents = read_file(file)?;
+        let replaced_contents = regex.replace(&contents, |caps: &Captures| {
+            let code = caps["code"].parse::<u64>().unwrap_or_default();
+            format!("{}{}", &caps["key"], code + 1)
+        });
+        write_file(file, replaced_contents.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Sets `date-released` to today in the `CITATION.cff` files being updated, if they have one
+fn update_release_dates(matching_files: &[PathBuf]) -> Result<(), BooperError> {
+    let regex = Regex::new(r#"(?m)^(?<key>date-released:[ \t]*["']?)\d{4}-\d{2}-\d{2}"#).unwrap();
+    let today = chrono::Local::now().date_naive().format("%Y-%m-%d");
+    for file in matching_files {
+        if FileKind::new(file) != FileKind::Citation {
+            continue;
+        }
+        let contents = read_file(file)?;
+        let replaced_contents = regex.replace(&contents, format!("${{key}}{today}"));
+        write_file(file, replaced_contents.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Whether a match of the kind's regex is a version booper should touch
+///
+/// `Cargo.toml` matches must be in `[package]` or `[workspace.package]`, `pyproject.toml` matches in `[project]` or `[tool.poetry]`
+/// and `pom.xml` matches directly in `<project>` so dependency and tool versions are left alone, and `Dockerfile` matches must be one
+/// of the configured forms
+fn is_version_match(
+    kind: FileKind,
+    contents: &str,
+    caps: &Captures,
+    dockerfile: &[DockerfileVersion],
+) -> bool {
+    match kind {
+        FileKind::Cargo => matches!(
+            toml_table(&contents[..caps.get_match().start()]).as_deref(),
+            Some("package" | "workspace.package")
+        ),
+        FileKind::Pyproject => matches!(
+            toml_table(&contents[..caps.get_match().start()]).as_deref(),
+            Some("project" | "tool.poetry")
+        ),
+        FileKind::Pom => open_xml_elements(&contents[..caps.get_match().start()]) == ["project"],
+        FileKind::Dockerfile if caps.name("arg").is_some() => {
+            dockerfile.contains(&DockerfileVersion::Arg)
+        }
+        FileKind::Dockerfile if caps.name("label").is_some() => {
+            dockerfile.contains(&DockerfileVersion::Label)
+        }
+        _ => true,
+    }
+}
+
+/// Name of the table the end of `toml` is in going by the last `[table]` header, `None` before the first one
+fn toml_table(toml: &str) -> Option<String> {
+    let header = toml
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| line.starts_with('['))?;
+    let (table, _) = header.strip_prefix('[')?.split_once(']')?;
+    Some(table.replace([' ', '\t'], ""))
+}
+
+/// Names of the elements still open at the end of `xml`, outermost first, skipping comments and declarations
+fn open_xml_elements(xml: &str) -> Vec<&str> {
+    let mut open = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else if rest.starts_with("<![CDATA[") {
+            rest.find("]]>").map(|end| end + 3)
+        } else {
+            rest.find('>').map(|end| end + 1)
+        };
+        let Some(end) = end else {
+            break;
+        };
+        let tag = &rest[1..end - 1];
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            if let Some(position) = open.iter().rposition(|open| *open == name) {
+                open.truncate(position);
+            }
+        } else if !tag.starts_with(['?', '!']) && !tag.ends_with('/') {
+            open.push(
+                tag.split(|c: char| c.is_whitespace())
+                    .next()
+                    .unwrap_or_default(),
+            );
+        }
+        rest = &rest[end..];
+    }
+    open
+}
+
+/// Asks the user to continue unless forced
+fn prompt(force: bool) -> Result<(), BooperError> {
+    if !force
+        && !dialoguer::Confirm::new()
+            .with_prompt("Do you want to continue?")
+            .interact()?
+    {
+        return Err(BooperError::Cancelled);
+    }
+    Ok(())
+}
+
+/// Always asks, even with -y / --force, as a downgrade is almost always a mistaken exact version
+fn confirm_downgrade(from_version: &Version, to_version: &Version) -> Result<(), BooperError> {
+    if !dialoguer::Confirm::new()
+        .with_prompt(format!(
+            "{to_version} is lower than the current version {from_version}, do you want to downgrade?"
+        ))
+        .interact()?
+    {
+        return Err(BooperError::Cancelled);
+    }
+    Ok(())
+}
+
+/// Matches commit messages produced by `template`, `{from_version}` can be anything as it is not known afterwards
+fn release_message_regex(template: &str, version: &str, tag: &str) -> Regex {
+    let pattern = regex::escape(template.trim())
+        .replace(r"\{version\}", &regex::escape(version))
+        .replace(r"\{from_version\}", ".+")
+        .replace(r"\{tag\}", &regex::escape(tag));
+    // A --commit-body paragraph may follow
+    Regex::new(&format!("(?s)^{pattern}(\n\n.*)?$")).unwrap()
+}
+
+/// Fails if the last tag is for a different version than the one in the files, skipped for prereleases which are not usually tagged
+fn check_last_tag(
+    from_version: &Version,
+    last_tag: &str,
+    tag_format: &TagFormat,
+) -> Result<(), BooperError> {
+    let stripped_last_tag = tag_format.strip(last_tag);
+    if !stripped_last_tag.is_empty()
+        && from_version.pre.is_empty()
+        && from_version.cmp_precedence(&parse_version(stripped_last_tag)?)
+            != std::cmp::Ordering::Equal
+    {
+        return Err(BooperError::TagMismatch {
+            tag: last_tag.to_owned(),
+            version: from_version.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Reads `[package].name` from a `Cargo.toml`
+fn cargo_package_name(contents: &str) -> Option<String> {
+    let manifest: toml::Table = contents.parse().ok()?;
+    Some(manifest.get("package")?.get("name")?.as_str()?.to_owned())
+}
+
+/// Reads `[package].version` falling back to `[workspace.package].version`
+///
+/// Members using `version.workspace = true` inherit the workspace version so are not treated as having their own
+fn cargo_manifest_version(contents: &str) -> Option<String> {
+    let manifest: toml::Table = contents.parse().ok()?;
+    let package_version = manifest
+        .get("package")
+        .and_then(|package| package.get("version"));
+    if let Some(toml::Value::String(version)) = package_version {
+        return Some(version.clone());
+    }
+    let workspace_version = manifest.get("workspace")?.get("package")?.get("version")?;
+    Some(workspace_version.as_str()?.to_owned())
+}
+
+/// Byte range of the top level `"version"` string in JSON, found by scanning so the formatting is kept on rewrite
+///
+/// Composer allows a leading `v` which is left out of the range so it is kept
+fn json_version(contents: &str) -> Option<Range<usize>> {
+    let range = string_values(contents, &[&["version"]])
+        .into_iter()
+        .next()?;
+    let start = range.start + usize::from(contents[range.clone()].starts_with('v'));
+    Some(start..range.end)
+}
+
+/// The line ending used by most lines of `contents`, defaulting to `\n`
+fn line_ending(contents: &str) -> &'static str {
+    let crlf = contents.matches("\r\n").count();
+    if crlf * 2 > contents.matches('\n').count() {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// The version written in `file`, if it has one
+fn detect_version(file: &Path, dockerfile: &[DockerfileVersion]) -> Option<String> {
+    let contents = std::fs::read_to_string(file).ok()?;
+    let kind = FileKind::new(file).configured(dockerfile);
+    let regex = match kind {
+        FileKind::Cargo => return cargo_manifest_version(&contents),
+        FileKind::Bare => return Some(contents.trim().to_owned()),
+        FileKind::Json => return Some(contents[json_version(&contents)?].to_owned()),
+        _ => kind.detect_regex()?,
+    };
+    let cap = regex
+        .captures_iter(&contents)
+        .find(|caps| is_version_match(kind, &contents, caps, dockerfile))?;
+    Some(cap.name("version")?.as_str().to_owned())
+}
+
+/// The version in `file` found by its `[[match]]` if it has one, otherwise by its kind
+fn detect_configured_version(file: &Path, config: &Config, root: &Path) -> Option<String> {
+    let Some(matcher) = config
+        .matchers
+        .iter()
+        .find(|matcher| root.join(matcher.file()) == file)
+    else {
+        return detect_version(file, &config.dockerfile);
+    };
+    let contents = std::fs::read_to_string(file).ok()?;
+    Some(contents[matcher.find(&contents)?].to_owned())
+}
+
+/// Adds missing minor and patch numbers so `1.2` becomes `1.2.0`, any prerelease or build is kept
+fn pad_version(version: &str) -> String {
+    let (core, rest) = version.split_at(version.find(['-', '+']).unwrap_or(version.len()));
+    let missing = 2usize.saturating_sub(core.matches('.').count());
+    format!("{core}{}{rest}", ".0".repeat(missing))
+}
+
+fn parse_version(version: &str) -> Result<Version, BooperError> {
+    Version::parse(version).map_err(|source| BooperError::InvalidVersion {
+        version: version.to_owned(),
+        source,
+    })
+}
+
+fn read_file(path: &Path) -> Result<String, BooperError> {
+    std::fs::read_to_string(path).map_err(|source| BooperError::File {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), BooperError> {
+    std::fs::write(path, contents).map_err(|source| BooperError::File {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn spawn_error(cmd: &Command) -> impl FnOnce(std::io::Error) -> BooperError {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    move |source| BooperError::Spawn { program, source }
+}
+
+fn echo(cmd: &Command, verbosity: Verbosity) {
+    if verbosity == Verbosity::Verbose {
+        eprintln!("+ {cmd:?}");
+    }
+}
+
+/// Sends the output of `cmd` to stderr so stdout is kept for `--json`, hiding it entirely when quiet
+fn redirect_stdout(cmd: &mut Command, verbosity: Verbosity) {
+    if verbosity == Verbosity::Quiet {
+        cmd.stdout(Stdio::null());
+    } else {
+        cmd.stdout(std::io::stderr());
+    }
+}
+
+/// Runs `cmd` with its stdout redirected
+fn status(cmd: &mut Command, verbosity: Verbosity) -> Result<ExitStatus, BooperError> {
+    echo(cmd, verbosity);
+    redirect_stdout(cmd, verbosity);
+    cmd.status().map_err(spawn_error(cmd))
+}
+
+/// Runs `cmd` capturing its output
+fn output(cmd: &mut Command, verbosity: Verbosity) -> Result<Output, BooperError> {
+    echo(cmd, verbosity);
+    cmd.output().map_err(spawn_error(cmd))
+}
+
+/// Runs `cmd` failing with `what` if it exits unsuccessfully
+fn run(cmd: &mut Command, what: &'static str, verbosity: Verbosity) -> Result<(), BooperError> {
+    let status = status(cmd, verbosity)?;
+    ensure(
+        status.success(),
+        BooperError::CommandFailed { what, stderr: None },
+    )
+}
+
+/// Runs `cmd` and on failure reports its stderr alongside `what`, useful when the cause is otherwise unclear like a missing signing key
+fn run_reporting_stderr(
+    cmd: &mut Command,
+    what: &'static str,
+    verbosity: Verbosity,
+) -> Result<(), BooperError> {
+    redirect_stdout(cmd, verbosity);
+    let output = output(cmd.stderr(Stdio::piped()), verbosity)?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() {
+        if verbosity != Verbosity::Quiet {
+            eprint!("{stderr}");
+        }
+        return Ok(());
+    }
+    let stderr = stderr.trim();
+    Err(BooperError::CommandFailed {
+        what,
+        stderr: (!stderr.is_empty()).then(|| stderr.to_owned()),
+    })
+}
+
+fn cargo_check(cargo: &Path, root: &Path, verbosity: Verbosity) -> Result<(), BooperError> {
+    run(
+        Command::new(cargo).args(["check", "-q"]).current_dir(root),
+        "cargo check",
+        verbosity,
+    )
+}
+
+/// Runs `command` through the platform shell
+fn shell(command: &str) -> Command {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    cmd.arg(command);
+    cmd
+}
+
+fn run_hook(hook: &str, root: &Path, verbosity: Verbosity) -> Result<(), BooperError> {
+    let status = status(shell(hook).current_dir(root), verbosity)?;
+    ensure(
+        status.success(),
+        BooperError::HookFailed {
+            hook: hook.to_owned(),
+            status,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    fn increment(increment: &str, current: &str) -> String {
+        increment
+            .parse::<VersionIncrement>()
+            .unwrap()
+            .increment(&version(current))
+            .to_string()
+    }
+
+    #[test]
+    fn core_increments_drop_the_prerelease() {
+        assert_eq!(increment("patch", "1.2.3"), "1.2.4");
+        assert_eq!(increment("minor", "1.2.3"), "1.3.0");
+        assert_eq!(increment("major", "1.2.3"), "2.0.0");
+        assert_eq!(increment("patch", "1.2.0-pre.3"), "1.2.1");
+        assert_eq!(increment("minor", "1.2.0-pre.3"), "1.3.0");
+        assert_eq!(increment("major", "1.2.0-pre.3"), "2.0.0");
+        assert_eq!(increment("patch", "1.2.3+build.4"), "1.2.4");
+    }
+
+    #[test]
+    fn other_increments() {
+        assert_eq!(increment("auto", "1.2.3"), "1.2.4");
+        assert_eq!(increment("auto", "1.2.3-rc.1"), "1.2.3");
+        assert_eq!(increment("strip", "1.2.3-rc.1+build.2"), "1.2.3");
+        assert_eq!(increment("pre", "1.2.3"), "1.2.3-pre.1");
+        assert_eq!(increment("pre", "1.2.3-alpha.1"), "1.2.3-alpha.2");
+        assert_eq!(increment("beta", "1.2.3-alpha.4"), "1.2.3-beta.1");
+        assert_eq!(increment("pre=dev", "1.2.3"), "1.2.3-dev.1");
+        assert_eq!(increment("minor-rc", "1.2.3"), "1.3.0-rc.1");
+        assert_eq!(increment("major-pre", "1.2.3-alpha.2"), "2.0.0-pre.1");
+        assert_eq!(increment("promote", "1.2.3"), "1.2.4-alpha.1");
+        assert_eq!(increment("promote", "1.2.4-beta.2"), "1.2.4-rc.1");
+        assert_eq!(increment("promote", "1.2.4-rc.3"), "1.2.4");
+        assert_eq!(increment("build", "1.2.3+build.4"), "1.2.3+build.5");
+        assert_eq!(increment("3.0.0+meta", "1.2.3"), "3.0.0+meta");
+    }
+
+    #[test]
+    fn to_version_keeps_the_prerelease_with_keep_pre() {
+        let cases = [
+            ("patch", "1.2.0-pre.3", "1.2.1", "1.2.1-pre.1"),
+            ("minor", "1.2.0-pre.3", "1.3.0", "1.3.0-pre.1"),
+            ("major", "1.2.0-pre.3", "2.0.0", "2.0.0-pre.1"),
+            ("patch", "1.2.3", "1.2.4", "1.2.4"),
+            ("minor", "1.2.3", "1.3.0", "1.3.0"),
+            ("major", "1.2.3", "2.0.0", "2.0.0"),
+            ("minor", "1.2.0-rc", "1.3.0", "1.3.0-rc.1"),
+            ("minor", "1.2.0-4", "1.3.0", "1.3.0-1"),
+            ("strip", "1.2.0-pre.3", "1.2.0", "1.2.0"),
+            ("pre", "1.2.0-pre.3", "1.2.0-pre.4", "1.2.0-pre.4"),
+        ];
+        let booper = Booper::try_parse_from(["booper"]).unwrap();
+        let keep_pre = Booper::try_parse_from(["booper", "--keep-pre"]).unwrap();
+        for (increment, from, expected, expected_keep_pre) in cases {
+            let increment: VersionIncrement = increment.parse().unwrap();
+            let from = version(from);
+            assert_eq!(booper.to_version(&increment, &from).to_string(), expected);
+            assert_eq!(
+                keep_pre.to_version(&increment, &from).to_string(),
+                expected_keep_pre
+            );
+        }
+    }
+
+    #[test]
+    fn to_version_sets_the_build() {
+        let booper = Booper::try_parse_from(["booper", "--build", "sha.abc"]).unwrap();
+        let to_version = booper.to_version(&VersionIncrement::Minor, &version("1.2.3+old"));
+        assert_eq!(to_version.to_string(), "1.3.0+sha.abc");
+    }
+
+    #[test]
+    fn prerelease_counter() {
+        let bump = |current: &str, label| {
+            bump_prerelease(&semver::Prerelease::new(current).unwrap(), label).to_string()
+        };
+        assert_eq!(bump("", None), "pre.1");
+        assert_eq!(bump("", Some("rc")), "rc.1");
+        assert_eq!(bump("alpha", None), "alpha.1");
+        assert_eq!(bump("alpha.1", None), "alpha.2");
+        assert_eq!(bump("alpha.1", Some("alpha")), "alpha.2");
+        assert_eq!(bump("alpha.1", Some("beta")), "beta.1");
+        assert_eq!(bump("alpha.x.9", None), "alpha.x.10");
+        assert_eq!(bump("3", None), "4");
+    }
+
+    #[test]
+    fn build_counter() {
+        let bump =
+            |current: &str| bump_build(&semver::BuildMetadata::new(current).unwrap()).to_string();
+        assert_eq!(bump(""), "build.1");
+        assert_eq!(bump("build.5"), "build.6");
+        assert_eq!(bump("7"), "8");
+        assert_eq!(bump("sha"), "sha.1");
+    }
+
+    #[test]
+    fn promote_moves_up_the_ladder() {
+        let ladder = ["dev".to_owned(), "rc".to_owned()];
+        assert_eq!(
+            promote_increment(&version("1.2.3"), &ladder),
+            VersionIncrement::Exact(version("1.2.4-dev.1"))
+        );
+        assert_eq!(
+            promote_increment(&version("1.2.4-dev.3"), &ladder),
+            VersionIncrement::Prerelease(Some("rc".to_owned()))
+        );
+        assert_eq!(
+            promote_increment(&version("1.2.4-rc.1"), &ladder),
+            VersionIncrement::StripPrerelease
+        );
+        assert_eq!(
+            promote_increment(&version("1.2.4-beta.1"), &ladder),
+            VersionIncrement::StripPrerelease
+        );
+        assert_eq!(
+            promote_increment(&version("1.2.3"), &[]),
+            VersionIncrement::Patch
+        );
+    }
+
+    #[test]
+    fn conventional_commits() {
+        let implied = |subjects: &[&str]| {
+            conventional_increment(&subjects.iter().map(|&s| s.to_owned()).collect::<Vec<_>>())
+        };
+        assert_eq!(implied(&[]), VersionIncrement::Patch);
+        assert_eq!(
+            implied(&["fix: typo", "docs: readme"]),
+            VersionIncrement::Patch
+        );
+        assert_eq!(
+            implied(&["fix: typo", "feat(cli): add flag"]),
+            VersionIncrement::Minor
+        );
+        assert_eq!(
+            implied(&["feat: add flag", "refactor!: drop api"]),
+            VersionIncrement::Major
+        );
+        assert_eq!(
+            implied(&["chore: BREAKING CHANGE in config"]),
+            VersionIncrement::Major
+        );
+        assert_eq!(
+            implied(&["feature: not conventional"]),
+            VersionIncrement::Patch
+        );
+    }
+
+    #[test]
+    fn tags_naming_the_version() {
+        assert!(tag_names_version("v1.2.3", "1.2.3"));
+        assert!(tag_names_version("release-1.2.3", "1.2.3"));
+        assert!(tag_names_version("core-v1.2.3", "1.2.3"));
+        assert!(!tag_names_version("v1.2.30", "1.2.3"));
+        assert!(!tag_names_version("v1.2.3-rc.1", "1.2.3"));
+        assert!(!tag_names_version("v11.2.3", "1.2.3"));
+        assert!(!tag_names_version("latest", "1.2.3"));
+    }
+}
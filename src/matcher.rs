@@ -0,0 +1,232 @@
+//! Finds the version by its key in files set up with `[[match]]` in `.booper.toml`, for formats booper does not know by name like front matter.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::config::deserialize_regex;
+
+/// How to find the version in `file`, selected by `format`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase", deny_unknown_fields)]
+pub enum Matcher {
+    /// The string at a dotted `key` path like `tool.version`, also accepting JSON5 comments, single quotes and unquoted keys
+    Json { file: PathBuf, key: String },
+    /// The string at a dotted `key` path, in the `+++` front matter if there is one
+    Toml { file: PathBuf, key: String },
+    /// The value at a dotted `key` path of nested mappings, in the `---` front matter if there is one
+    Yaml { file: PathBuf, key: String },
+    /// The `version` capture group of the first match of `pattern`
+    Regex {
+        file: PathBuf,
+        #[serde(deserialize_with = "deserialize_regex")]
+        pattern: Regex,
+    },
+}
+
+impl Matcher {
+    pub fn file(&self) -> &Path {
+        match self {
+            Self::Json { file, .. }
+            | Self::Toml { file, .. }
+            | Self::Yaml { file, .. }
+            | Self::Regex { file, .. } => file,
+        }
+    }
+
+    /// Byte range of the version in `contents`
+    pub fn find(&self, contents: &str) -> Option<Range<usize>> {
+        match self {
+            Self::Json { key, .. } => {
+                let path: Vec<&str> = key.split('.').collect();
+                string_values(contents, &[&path]).into_iter().next()
+            }
+            Self::Toml { key, .. } => {
+                let region = front_matter(contents, "+++");
+                toml_value(&contents[region.clone()], key).map(|range| offset(range, region.start))
+            }
+            Self::Yaml { key, .. } => {
+                let region = front_matter(contents, "---");
+                yaml_value(&contents[region.clone()], key).map(|range| offset(range, region.start))
+            }
+            Self::Regex { pattern, .. } => {
+                Some(pattern.captures(contents)?.name("version")?.range())
+            }
+        }
+    }
+}
+
+fn offset(range: Range<usize>, by: usize) -> Range<usize> {
+    range.start + by..range.end + by
+}
+
+/// The part of `contents` between `delimiter` lines when it starts with one, otherwise all of it
+fn front_matter(contents: &str, delimiter: &str) -> Range<usize> {
+    let Some(rest) = contents.strip_prefix(delimiter) else {
+        return 0..contents.len();
+    };
+    let start = delimiter.len();
+    let end = rest
+        .match_indices(delimiter)
+        .map(|(i, _)| start + i)
+        .find(|&i| contents[..i].ends_with('\n'))
+        .unwrap_or(contents.len());
+    start..end
+}
+
+/// Byte range of the string at dotted `key` in TOML, going by the `[table]` headers and dotted keys on each line
+fn toml_value(toml: &str, key: &str) -> Option<Range<usize>> {
+    let key = key.replace([' ', '\t'], "");
+    let mut table = String::new();
+    let mut line_start = 0;
+    for line in toml.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix('[') {
+            table = header
+                .trim_start_matches('[')
+                .split(']')
+                .next()
+                .unwrap_or_default()
+                .replace([' ', '\t'], "");
+            continue;
+        }
+        let Some((line_key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let line_key = line_key.replace([' ', '\t'], "");
+        let full_key = if table.is_empty() {
+            line_key
+        } else {
+            format!("{table}.{line_key}")
+        };
+        if full_key == key {
+            let value_start = start + line.len() - value.len();
+            return quoted(value).map(|range| offset(range, value_start));
+        }
+    }
+    None
+}
+
+/// Byte range of the value at dotted `key` in YAML, going by the indentation of each `key:` line
+fn yaml_value(yaml: &str, key: &str) -> Option<Range<usize>> {
+    // The indentation and key of each mapping holding the current line
+    let mut parents: Vec<(usize, &str)> = Vec::new();
+    let mut line_start = 0;
+    for line in yaml.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        let content = line.trim_start();
+        if content.trim().is_empty() || content.starts_with(['#', '-']) {
+            continue;
+        }
+        let indent = line.len() - content.len();
+        let Some((line_key, value)) = content.split_once(':') else {
+            continue;
+        };
+        while parents.last().is_some_and(|&(parent, _)| parent >= indent) {
+            parents.pop();
+        }
+        let line_key = line_key.trim().trim_matches(['"', '\'']);
+        if value.trim().is_empty() || value.trim_start().starts_with('#') {
+            parents.push((indent, line_key));
+            continue;
+        }
+        let full_key: Vec<&str> = parents
+            .iter()
+            .map(|&(_, parent)| parent)
+            .chain([line_key])
+            .collect();
+        if full_key.join(".") == key {
+            let value_start = start + line.len() - value.len();
+            let range = quoted(value).or_else(|| {
+                let unquoted = value.split(" #").next().unwrap_or_default();
+                let leading = unquoted.len() - unquoted.trim_start().len();
+                Some(leading..unquoted.trim_end().len())
+            })?;
+            return Some(offset(range, value_start));
+        }
+    }
+    None
+}
+
+/// Range of the contents of the string that `value` starts with, in either quote
+fn quoted(value: &str) -> Option<Range<usize>> {
+    let leading = value.len() - value.trim_start().len();
+    let quote = value[leading..]
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\''))?;
+    let start = leading + 1;
+    let end = start + value[start..].find(quote)?;
+    Some(start..end)
+}
+
+/// Byte ranges of the string values at any of the key `paths`, found by scanning so the formatting is kept on rewrite
+///
+/// Comments, single quoted strings and unquoted keys are understood so this also works for JSON5
+pub fn string_values(json: &str, paths: &[&[&str]]) -> Vec<Range<usize>> {
+    // The key each open object is at, `None` for arrays
+    let mut stack: Vec<Option<&str>> = Vec::new();
+    let mut expecting_key = false;
+    let mut ranges = Vec::new();
+    let bytes = json.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && bytes[end] != quote {
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+                let end = end.min(bytes.len());
+                if expecting_key {
+                    if let Some(key) = stack.last_mut() {
+                        *key = Some(&json[start..end]);
+                    }
+                    expecting_key = false;
+                } else if paths.iter().any(|path| {
+                    path.len() == stack.len()
+                        && path.iter().zip(&stack).all(|(a, b)| Some(*a) == *b)
+                }) {
+                    ranges.push(start..end);
+                }
+                i = end;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = json[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = json[i..].find("*/").map_or(bytes.len(), |end| i + end + 1);
+            }
+            b'{' => {
+                stack.push(Some(""));
+                expecting_key = true;
+            }
+            b'[' => stack.push(None),
+            b'}' | b']' => {
+                stack.pop();
+            }
+            b',' => expecting_key = stack.last().is_some_and(Option::is_some),
+            byte if expecting_key
+                && (byte.is_ascii_alphabetic() || matches!(byte, b'_' | b'$')) =>
+            {
+                let end = json[i..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$')))
+                    .map_or(bytes.len(), |end| i + end);
+                if let Some(key) = stack.last_mut() {
+                    *key = Some(&json[i..end]);
+                }
+                expecting_key = false;
+                i = end - 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    ranges
+}
@@ -0,0 +1,44 @@
+//! Keeps the root package version in `package-lock.json` in step with `package.json`.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::BooperError;
+use crate::matcher::string_values;
+use crate::{read_file, write_file};
+
+pub const LOCK_FILE: &str = "package-lock.json";
+
+/// Where npm records the root package version, the `packages` entry is only in lockfile version 2 and later
+const VERSION_PATHS: [&[&str]; 2] = [&["version"], &["packages", "", "version"]];
+
+/// Sets the root package version in the lock file at `root` from one of `from_versions` to `to_version`
+///
+/// Returns the path of the lock file if it was changed, a missing lock file is left alone
+pub fn update(
+    root: &Path,
+    from_versions: &[String],
+    to_version: &str,
+) -> Result<Option<PathBuf>, BooperError> {
+    let path = root.join(LOCK_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = read_file(&path)?;
+    let mut new_contents = contents.clone();
+    let mut changed = false;
+    // Back to front so earlier ranges stay valid
+    for range in string_values(&contents, &VERSION_PATHS).into_iter().rev() {
+        if from_versions
+            .iter()
+            .any(|from_version| *from_version == contents[range.clone()])
+        {
+            new_contents.replace_range(range, to_version);
+            changed = true;
+        }
+    }
+    if !changed {
+        return Ok(None);
+    }
+    write_file(&path, &new_contents)?;
+    Ok(Some(path))
+}
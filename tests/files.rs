@@ -0,0 +1,460 @@
+//! Runs booper against sample projects in temporary git repositories and checks what it did.
+
+#![expect(unused_crate_dependencies, reason = "the tests only run the binary")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// A git repository in the temp directory that is removed when dropped
+struct Project {
+    dir: PathBuf,
+}
+
+impl Project {
+    fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let dir = std::env::temp_dir().join(format!("booper-test-{name}-{}", std::process::id()));
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        std::fs::create_dir_all(&dir).unwrap();
+        let project = Self { dir };
+        for (file, contents) in files {
+            project.write(file, contents);
+        }
+        project.git(&["init", "-q"]);
+        project.git(&["config", "user.name", "Booper Test"]);
+        project.git(&["config", "user.email", "test@example.com"]);
+        project
+    }
+
+    /// Runs git in the repository, returning what it printed
+    fn git(&self, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&self.dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// Commits everything in the working tree
+    fn commit(&self, message: &str) {
+        self.git(&["add", "-A"]);
+        self.git(&["commit", "-q", "-m", message]);
+    }
+
+    fn write(&self, file: &str, contents: &str) {
+        let path = self.dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn read(&self, file: &str) -> String {
+        std::fs::read_to_string(self.dir.join(file)).unwrap()
+    }
+
+    /// Runs booper without prompting or checking the build
+    fn run(&self, args: &[&str]) -> Output {
+        self.run_in(".", args)
+    }
+
+    /// Like [`Self::run`] but from the subdirectory `dir`
+    fn run_in(&self, dir: &str, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_booper"))
+            .args(["-y", "--allow-dirty", "--no-check"])
+            .args(args)
+            .current_dir(self.dir.join(dir))
+            .output()
+            .unwrap()
+    }
+
+    /// Runs booper with only `args`, for subcommands and flags that conflict with the ones [`Self::run`] adds
+    fn run_raw(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_booper"))
+            .args(args)
+            .current_dir(&self.dir)
+            .output()
+            .unwrap()
+    }
+
+    /// Like [`Self::run`] but failing the test if booper fails
+    fn boop(&self, args: &[&str]) -> Output {
+        let output = self.run(args);
+        assert!(
+            output.status.success(),
+            "booper failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output
+    }
+}
+
+impl Drop for Project {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn package_json_top_level_version() {
+    let project = Project::new(
+        "package-json",
+        &[(
+            "package.json",
+            "{\n  \"publishConfig\": {\n    \"version\": \"0.1.0\"\n  },\n  \"version\": \"1.2.3\",\n  \"dependencies\": {\n    \"version\": \"1.2.3\"\n  }\n}\n",
+        )],
+    );
+    project.boop(&["patch"]);
+    assert_eq!(
+        project.read("package.json"),
+        "{\n  \"publishConfig\": {\n    \"version\": \"0.1.0\"\n  },\n  \"version\": \"1.2.4\",\n  \"dependencies\": {\n    \"version\": \"1.2.3\"\n  }\n}\n"
+    );
+}
+
+#[test]
+fn pyproject_project_version() {
+    let project = Project::new(
+        "pyproject",
+        &[(
+            "pyproject.toml",
+            "[project]\nname = \"demo\"\nversion = \"1.2.3\"\nrequires-python = \">=3.8\"\n\n[tool.foo]\nversion = \"1.2.3\"\n",
+        )],
+    );
+    project.boop(&["patch"]);
+    assert_eq!(
+        project.read("pyproject.toml"),
+        "[project]\nname = \"demo\"\nversion = \"1.2.4\"\nrequires-python = \">=3.8\"\n\n[tool.foo]\nversion = \"1.2.3\"\n"
+    );
+}
+
+#[test]
+fn pyproject_poetry_version() {
+    let project = Project::new(
+        "poetry",
+        &[(
+            "pyproject.toml",
+            "[tool.poetry]\nname = \"demo\"\nversion = \"1.2.3\"\n\n[tool.poetry.dependencies]\nrequests = { version = \"1.2.3\" }\n",
+        )],
+    );
+    project.boop(&["minor"]);
+    assert_eq!(
+        project.read("pyproject.toml"),
+        "[tool.poetry]\nname = \"demo\"\nversion = \"1.3.0\"\n\n[tool.poetry.dependencies]\nrequests = { version = \"1.2.3\" }\n"
+    );
+}
+
+#[test]
+fn env_every_assignment() {
+    let project = Project::new(
+        "env",
+        &[(".env", "VERSION=\"1.0.0\"\n# OLD_VERSION=\"1.0.0\"\n")],
+    );
+    project.boop(&["patch"]);
+    assert_eq!(
+        project.read(".env"),
+        "VERSION=\"1.0.1\"\n# OLD_VERSION=\"1.0.1\"\n"
+    );
+}
+
+#[test]
+fn hidden_directories_left_alone() {
+    let project = Project::new(
+        "hidden",
+        &[
+            ("VERSION", "1.2.3\n"),
+            (".github/workflows/ci.yml", "uses: some/action@1.2.3\n"),
+            (".config/version.txt", "1.2.3\n"),
+        ],
+    );
+    project.boop(&["--file", ".config/version.txt", "patch"]);
+    assert_eq!(project.read("VERSION"), "1.2.4\n");
+    assert_eq!(
+        project.read(".github/workflows/ci.yml"),
+        "uses: some/action@1.2.3\n"
+    );
+    assert_eq!(project.read(".config/version.txt"), "1.2.4\n");
+}
+
+#[test]
+fn deno_jsonc_commented_version() {
+    let project = Project::new(
+        "deno-jsonc",
+        &[(
+            "deno.jsonc",
+            "{\n  /* \"version\": \"8.8.8\" */\n  // \"version\": \"8.8.8\",\n  \"version\": \"1.2.3\"\n}\n",
+        )],
+    );
+    project.boop(&["patch"]);
+    assert_eq!(
+        project.read("deno.jsonc"),
+        "{\n  /* \"version\": \"8.8.8\" */\n  // \"version\": \"8.8.8\",\n  \"version\": \"1.2.4\"\n}\n"
+    );
+}
+
+#[test]
+fn csproj_in_subdirectory() {
+    let project = Project::new(
+        "csproj",
+        &[(
+            "src/Demo/Demo.csproj",
+            "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <PropertyGroup>\n    <Version>1.2.3</Version>\n  </PropertyGroup>\n</Project>\n",
+        )],
+    );
+    project.boop(&["minor"]);
+    assert_eq!(
+        project.read("src/Demo/Demo.csproj"),
+        "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <PropertyGroup>\n    <Version>1.3.0</Version>\n  </PropertyGroup>\n</Project>\n"
+    );
+}
+
+#[test]
+fn failed_hook_exits_with_one() {
+    let project = Project::new("hook", &[("VERSION", "1.2.3\n")]);
+    for code in ["2", "3"] {
+        let output = project.run(&["--pre-hook", &format!("exit {code}"), "patch"]);
+        assert_eq!(output.status.code(), Some(1));
+    }
+}
+
+#[test]
+fn shell_script_assignments() {
+    for (assignment, bumped) in [
+        ("VERSION=\"1.2.3\"", "VERSION=\"1.2.4\""),
+        ("VERSION='1.2.3'", "VERSION='1.2.4'"),
+        ("VERSION=1.2.3", "VERSION=1.2.4"),
+    ] {
+        let project = Project::new(
+            "install-sh",
+            &[(
+                "install.sh",
+                &format!(
+                    "#!/bin/sh\nNODE_VERSION=18.0.0\n{assignment}\ncurl -L \"https://example.com/$VERSION\"\n"
+                ),
+            )],
+        );
+        project.boop(&["--file", "install.sh", "patch"]);
+        assert_eq!(
+            project.read("install.sh"),
+            format!(
+                "#!/bin/sh\nNODE_VERSION=18.0.0\n{bumped}\ncurl -L \"https://example.com/$VERSION\"\n"
+            )
+        );
+    }
+}
+
+#[test]
+fn run_from_subdirectory() {
+    let project = Project::new(
+        "subdirectory",
+        &[
+            ("VERSION", "1.2.3\n"),
+            ("README.md", "Install version 1.2.3\n"),
+            (
+                ".booper.toml",
+                "[[replace]]\nfile = \"README.md\"\npattern = 'version \\d+\\.\\d+\\.\\d+'\n",
+            ),
+            ("docs/guide.md", "Nothing to see\n"),
+        ],
+    );
+    let output = project.run_in("docs", &["--pre-hook", "touch hooked", "patch"]);
+    assert!(
+        output.status.success(),
+        "booper failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(project.read("VERSION"), "1.2.4\n");
+    assert_eq!(project.read("README.md"), "Install version 1.2.4\n");
+    assert!(project.dir.join("hooked").exists());
+    assert!(!project.dir.join("docs/hooked").exists());
+}
+
+#[test]
+fn dry_run_verify_leaves_files_alone() {
+    let project = Project::new("dry-run-verify", &[("VERSION", "1.2.3\n")]);
+    let verify = |verify_cmd: &str| {
+        project.run_raw(&[
+            "-y",
+            "--allow-dirty",
+            "--dry-run",
+            "--verify",
+            "--verify-cmd",
+            verify_cmd,
+            "patch",
+        ])
+    };
+    let output = verify("grep -q 1.2.4 VERSION && touch verified");
+    assert!(
+        output.status.success(),
+        "booper failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!project.dir.join("verified").exists());
+    assert!(!verify("grep -q 1.2.3 VERSION").status.success());
+    assert_eq!(project.read("VERSION"), "1.2.3\n");
+}
+
+#[test]
+fn dry_run_retag_leaves_tags_alone() {
+    let project = Project::new("dry-run-retag", &[("VERSION", "0.1.1\n")]);
+    project.commit("Initial commit");
+    project.boop(&["--retag", "--dry-run"]);
+    assert_eq!(project.git(&["tag"]), "");
+
+    project.git(&["tag", "v0.1.1"]);
+    let tagged = project.git(&["rev-parse", "v0.1.1"]);
+    project.write("README.md", "Released\n");
+    project.commit("Add a readme");
+    let output = project.run(&[
+        "--retag",
+        "--force-tag",
+        "--dry-run",
+        "--exit-nonzero-on-change",
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(project.git(&["tag"]), "v0.1.1\n");
+    assert_eq!(project.git(&["rev-parse", "v0.1.1"]), tagged);
+}
+
+#[test]
+fn root_release_after_package_release() {
+    let project = Project::new(
+        "package-tags",
+        &[
+            (
+                "Cargo.toml",
+                "[package]\nname = \"demo\"\nversion = \"1.2.3\"\n",
+            ),
+            (
+                "core/Cargo.toml",
+                "[package]\nname = \"core\"\nversion = \"1.2.9\"\n",
+            ),
+        ],
+    );
+    project.commit("Initial commit");
+    project.git(&["tag", "v1.2.3"]);
+    project.boop(&["--package", "core", "--commit", "--tag", "minor"]);
+    assert_eq!(project.git(&["tag"]), "core-v1.3.0\nv1.2.3\n");
+    project.boop(&["--commit", "--tag", "patch"]);
+    assert_eq!(project.git(&["tag"]), "core-v1.3.0\nv1.2.3\nv1.2.4\n");
+    assert_eq!(
+        project.read("Cargo.toml"),
+        "[package]\nname = \"demo\"\nversion = \"1.2.4\"\n"
+    );
+}
+
+#[test]
+fn undo_custom_tag_format() {
+    let project = Project::new("undo-tag-format", &[("VERSION", "1.2.3\n")]);
+    project.commit("Initial commit");
+    project.boop(&[
+        "--commit",
+        "--tag",
+        "--tag-format",
+        "release-{version}",
+        "patch",
+    ]);
+    assert_eq!(project.git(&["tag"]), "release-1.2.4\n");
+    let output = project.run_raw(&["undo", "-y", "--tag-format", "release-{version}"]);
+    assert!(output.status.success());
+    assert_eq!(project.git(&["tag"]), "");
+    assert_eq!(project.read("VERSION"), "1.2.3\n");
+
+    project.boop(&["--commit", "--tag", "patch"]);
+    project.git(&["tag", "unrelated"]);
+    assert!(!project.run_raw(&["undo", "-y"]).status.success());
+    assert_eq!(project.read("VERSION"), "1.2.4\n");
+}
+
+#[test]
+fn undo_package_release() {
+    let project = Project::new(
+        "undo-package",
+        &[
+            (
+                "Cargo.toml",
+                "[package]\nname = \"demo\"\nversion = \"1.2.3\"\n",
+            ),
+            (
+                "core/Cargo.toml",
+                "[package]\nname = \"core\"\nversion = \"1.2.9\"\n",
+            ),
+        ],
+    );
+    project.commit("Initial commit");
+    project.boop(&["--package", "core", "--commit", "--tag", "minor"]);
+    let output = project.run_raw(&["undo", "-y", "--package", "core"]);
+    assert!(output.status.success());
+    assert_eq!(project.git(&["tag"]), "");
+    assert_eq!(
+        project.read("core/Cargo.toml"),
+        "[package]\nname = \"core\"\nversion = \"1.2.9\"\n"
+    );
+}
+
+#[test]
+fn list_extra_file() {
+    let project = Project::new(
+        "list-file",
+        &[("VERSION", "1.2.3\n"), ("docs/version.txt", "1.2.3\n")],
+    );
+    let output = project.run_raw(&["list", "--file", "docs/version.txt"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "./VERSION: 1.2.3\n./docs/version.txt: 1.2.3\nConsistent version 1.2.3\n"
+    );
+}
+
+#[test]
+fn list_workspace_members() {
+    let project = Project::new(
+        "list-workspace",
+        &[
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"crates/*\"]\n\n[package]\nname = \"demo\"\nversion = \"1.2.3\"\n",
+            ),
+            (
+                "crates/core/Cargo.toml",
+                "[package]\nname = \"core\"\nversion = \"1.2.3\"\n",
+            ),
+        ],
+    );
+    let output = project.run_raw(&["list", "--workspace"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "./Cargo.toml: 1.2.3\n./crates/core/Cargo.toml: 1.2.3\nConsistent version 1.2.3\n"
+    );
+}
+
+#[test]
+fn cargo_rust_version_left_alone() {
+    let project = Project::new(
+        "rust-version",
+        &[(
+            "Cargo.toml",
+            "[package]\nname = \"demo\"\nrust-version = \"1.2.3\"\nversion = \"1.2.3\"\n",
+        )],
+    );
+    project.boop(&["patch"]);
+    assert_eq!(
+        project.read("Cargo.toml"),
+        "[package]\nname = \"demo\"\nrust-version = \"1.2.3\"\nversion = \"1.2.4\"\n"
+    );
+}
+
+#[test]
+fn invalid_app_version_changes_nothing() {
+    let chart = "apiVersion: v2\nname: demo\nversion: 1.2.3\nappVersion: \"1.16\"\n";
+    let project = Project::new("app-version", &[("Chart.yaml", chart)]);
+    let output = project.run(&["--app-version", "patch"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1.16"));
+    assert_eq!(project.read("Chart.yaml"), chart);
+}